@@ -1,5 +1,5 @@
 import {createApp} from "vue";
-import {fetchCsrfToken} from "common"
+import {fetchCsrfToken, fetchJson} from "common"
 
 createApp({
     data() {
@@ -16,17 +16,9 @@ createApp({
             fetchCsrfToken().then(token => this.csrf_token = token);
         },
         getBucketList() {
-            fetch('/bucket-list/all')
+            fetchJson('/bucket-list/all')
                 .then(res => res.json())
-                .then(data => {
-                    this.bucket_list = data;
-                    this.formatDate();
-                });
-        },
-        formatDate() {
-            this.bucket_list.forEach(item => {
-                item.timestamp = new Date(item.timestamp).toLocaleString();
-            });
+                .then(data => this.bucket_list = data);
         },
         addToBucketList() {
             let json = {
@@ -34,7 +26,7 @@ createApp({
                 description: this.input_description
             }
             let token = this.csrf_token;
-            fetch('/bucket-list/add', {
+            fetchJson('/bucket-list/add', {
                 method: 'POST',
                 headers: {
                     'Content-Type': 'application/json',
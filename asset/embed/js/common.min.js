@@ -1,2 +1,15 @@
 function fetchCsrfToken(){return fetch("/csrf/token").then(function(res){return res.json();}).then(function(res){return res['token'];})}
-export{fetchCsrfToken};
\ No newline at end of file
+function dismissFlash(element){element.remove();}
+function bindFlash(element){if(element.dataset.flashBound){return;}
+element.dataset.flashBound="true";let button=element.querySelector('.flash-dismiss');if(button){button.addEventListener('click',function(){dismissFlash(element);});}
+let timeout=parseInt(element.dataset.timeout,10);if(timeout>0){setTimeout(function(){dismissFlash(element);},timeout);}}
+function bindFlashes(){document.querySelectorAll('.flash-message').forEach(bindFlash);}
+function showFlash(flash){let stack=document.getElementById('flash-stack');if(!stack){return;}
+let element=document.createElement('div');element.className='flash-message flash-message-'+flash.severity;element.setAttribute('role','alert');if(flash.timeout){element.dataset.timeout=flash.timeout;}
+let body=document.createElement('div');body.className='flash-body';if(flash.title){let title=document.createElement('strong');title.className='flash-title';title.textContent=flash.title;body.append(title);}
+if(flash.message){let message=document.createElement('span');message.className='flash-text';message.textContent=flash.message;body.append(message);}
+let button=document.createElement('button');button.type='button';button.className='flash-dismiss';button.setAttribute('aria-label',stack.dataset.dismissLabel);button.textContent='×';element.append(body,button);stack.append(element);bindFlash(element);}
+function showFlashes(res){let header=res.headers.get('X-Flash');if(header){JSON.parse(header).forEach(showFlash);}
+return res;}
+function fetchJson(url,options={}){let headers=Object.assign({'Accept':'application/json','X-Requested-With':'XMLHttpRequest'},options.headers);return fetch(url,Object.assign({},options,{headers})).then(showFlashes);}
+export{fetchCsrfToken,bindFlashes,showFlash,showFlashes,fetchJson};
\ No newline at end of file
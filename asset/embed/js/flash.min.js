@@ -0,0 +1 @@
+import{bindFlashes}from "common";bindFlashes();
\ No newline at end of file
@@ -0,0 +1,8 @@
+# Bucket list
+bucket-list-title = قائمة الأمنيات
+bucket-list-id = المعرّف
+bucket-list-name = الاسم
+bucket-list-description = الوصف
+bucket-list-timestamp = الوقت
+bucket-list-add = إضافة
+flash-bucket-added = تمت إضافة «{ $name }»
@@ -0,0 +1,94 @@
+# Layout
+site-name = قصيدة صغيرة
+page-untitled = بلا عنوان
+skip-to-content = انتقل إلى المحتوى
+
+# Navigation
+nav-label = التنقل الرئيسي
+nav-home = الرئيسية
+nav-bucket-list = قائمة الأمنيات
+nav-user = المستخدم
+nav-hello-user = مرحبًا، { $username }
+nav-visitor-login = أنت زائر، انقر هنا لتسجيل الدخول
+nav-language = اللغة
+nav-language-auto = لغة المتصفح
+nav-language-submit = تغيير
+nav-theme = المظهر
+nav-theme-system = النظام
+nav-theme-light = فاتح
+nav-theme-dark = داكن
+nav-theme-submit = تطبيق
+
+# Flash messages
+flash-login-succeeded = تم تسجيل الدخول بنجاح
+flash-login-failed = فشل تسجيل الدخول
+flash-logout-succeeded = تم تسجيل الخروج بنجاح
+flash-register-succeeded = تم التسجيل بنجاح
+flash-register-failed = فشل التسجيل
+flash-language-failed = تعذّر حفظ اللغة
+flash-time-zone-saved = تم حفظ المنطقة الزمنية
+flash-time-zone-failed = تعذّر حفظ المنطقة الزمنية
+flash-dismiss = إغلاق
+
+# Relative time
+relative-time-now = الآن
+relative-time-minutes-ago = { $count ->
+    [one] منذ دقيقة
+    [two] منذ دقيقتين
+    [few] منذ { $count } دقائق
+   *[other] منذ { $count } دقيقة
+}
+relative-time-hours-ago = { $count ->
+    [one] منذ ساعة
+    [two] منذ ساعتين
+    [few] منذ { $count } ساعات
+   *[other] منذ { $count } ساعة
+}
+relative-time-days-ago = { $count ->
+    [one] منذ يوم
+    [two] منذ يومين
+    [few] منذ { $count } أيام
+   *[other] منذ { $count } يومًا
+}
+relative-time-months-ago = { $count ->
+    [one] منذ شهر
+    [two] منذ شهرين
+    [few] منذ { $count } أشهر
+   *[other] منذ { $count } شهرًا
+}
+relative-time-years-ago = { $count ->
+    [one] منذ سنة
+    [two] منذ سنتين
+    [few] منذ { $count } سنوات
+   *[other] منذ { $count } سنة
+}
+relative-time-in-minutes = { $count ->
+    [one] خلال دقيقة
+    [two] خلال دقيقتين
+    [few] خلال { $count } دقائق
+   *[other] خلال { $count } دقيقة
+}
+relative-time-in-hours = { $count ->
+    [one] خلال ساعة
+    [two] خلال ساعتين
+    [few] خلال { $count } ساعات
+   *[other] خلال { $count } ساعة
+}
+relative-time-in-days = { $count ->
+    [one] خلال يوم
+    [two] خلال يومين
+    [few] خلال { $count } أيام
+   *[other] خلال { $count } يومًا
+}
+relative-time-in-months = { $count ->
+    [one] خلال شهر
+    [two] خلال شهرين
+    [few] خلال { $count } أشهر
+   *[other] خلال { $count } شهرًا
+}
+relative-time-in-years = { $count ->
+    [one] خلال سنة
+    [two] خلال سنتين
+    [few] خلال { $count } سنوات
+   *[other] خلال { $count } سنة
+}
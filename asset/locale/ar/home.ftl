@@ -0,0 +1,5 @@
+# Home
+home-title = تمرين Rust و Vue
+home-intro = هذا تمرين Rust و Vue.
+home-exercise = التمرين { $number }
+home-count = العدد:
@@ -0,0 +1,9 @@
+# Language
+language-id = ar
+language-name = العربية
+
+# Formatting, chrono strftime patterns
+format-date = %d/%m/%Y
+format-datetime = %d/%m/%Y %H:%M
+format-number-decimal = ٫
+format-number-group = ٬
@@ -0,0 +1,24 @@
+# User page
+user-title = المستخدم: { $username }
+user-visitor-title = زائر
+user-welcome = مرحبًا بك في صفحة المستخدم!
+user-logged-in-as = أنت مسجّل الدخول بصفتك المستخدم «{ $username }».
+user-logout-hint = يمكنك تسجيل الخروج بالنقر على الزر أدناه.
+user-logout = تسجيل الخروج
+user-visitor-logged-in = أنت مسجّل الدخول بصفتك زائرًا.
+user-visitor-login-hint = يمكنك تسجيل الدخول بصفتك مستخدمًا بالنقر على الزر أدناه.
+user-login-as-user = تسجيل الدخول بصفتك مستخدمًا
+user-current-time = الساعة الآن { $time } ({ $timeZone }).
+user-time-zone = المنطقة الزمنية
+user-time-zone-utc = UTC (الافتراضي)
+user-time-zone-submit = حفظ المنطقة الزمنية
+
+# Login and register
+user-login-title = تسجيل الدخول
+user-login-submit = دخول
+user-register-hint = إذا لم يكن لديك حساب، يمكنك التسجيل بالنقر على الزر أدناه.
+user-register-title = التسجيل
+user-register-submit = تسجيل
+user-username = اسم المستخدم
+user-password = كلمة المرور
+user-password-confirm = تأكيد كلمة المرور
@@ -0,0 +1,28 @@
+# Validation
+validate-cannot-be-empty = لا يمكن أن يكون فارغًا
+
+validate-min-length =
+    يجب ألا يقل عن { $min ->
+        [one] حرف واحد
+        [two] حرفين
+        [few] { $min } أحرف
+        *[other] { $min } حرفًا
+    }
+validate-max-length =
+    يجب ألا يزيد عن { $max ->
+        [one] حرف واحد
+        [two] حرفين
+        [few] { $max } أحرف
+        *[other] { $max } حرفًا
+    }
+
+validate-must-have-special-chars = يجب أن يحتوي على رمز خاص واحد على الأقل
+validate-must-have-uppercase-and-lowercase = يجب أن يحتوي على حرف كبير وحرف صغير واحد على الأقل
+validate-must-have-uppercase = يجب أن يحتوي على حرف كبير واحد على الأقل
+validate-must-have-lowercase = يجب أن يحتوي على حرف صغير واحد على الأقل
+validate-must-have-digit = يجب أن يحتوي على رقم واحد على الأقل
+
+validate-password-does-not-match = غير متطابق
+validate-username-taken = مستخدم بالفعل
+
+validate-password-entropy = يجب أن تتجاوز درجة قوة كلمة المرور { $min }، جرّب استخدام مدير كلمات المرور؟
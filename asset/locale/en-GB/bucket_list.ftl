@@ -0,0 +1,8 @@
+# Bucket list
+bucket-list-title = Bucket List
+bucket-list-id = ID
+bucket-list-name = Name
+bucket-list-description = Description
+bucket-list-timestamp = Timestamp
+bucket-list-add = Add
+flash-bucket-added = "{ $name }" added to the list
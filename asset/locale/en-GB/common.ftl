@@ -0,0 +1,74 @@
+# Layout
+site-name = A Little Poem
+page-untitled = Untitled
+skip-to-content = Skip to content
+
+# Navigation
+nav-label = Main navigation
+nav-home = Home
+nav-bucket-list = Bucket List
+nav-user = User
+nav-hello-user = Hello, { $username }
+nav-visitor-login = You're a visitor, click here to login
+nav-language = Language
+nav-language-auto = Browser language
+nav-language-submit = Change
+nav-theme = Theme
+nav-theme-system = System
+nav-theme-light = Light
+nav-theme-dark = Dark
+nav-theme-submit = Apply
+
+# Flash messages
+flash-login-succeeded = Login succeeded
+flash-login-failed = Login failed
+flash-logout-succeeded = Logout succeeded
+flash-register-succeeded = Register succeeded
+flash-register-failed = Register failed
+flash-language-failed = Language could not be saved
+flash-time-zone-saved = Time zone saved
+flash-time-zone-failed = Time zone could not be saved
+flash-dismiss = Dismiss
+
+# Relative time
+relative-time-now = just now
+relative-time-minutes-ago = { $count ->
+    [one] 1 minute ago
+   *[other] { $count } minutes ago
+}
+relative-time-hours-ago = { $count ->
+    [one] 1 hour ago
+   *[other] { $count } hours ago
+}
+relative-time-days-ago = { $count ->
+    [one] 1 day ago
+   *[other] { $count } days ago
+}
+relative-time-months-ago = { $count ->
+    [one] 1 month ago
+   *[other] { $count } months ago
+}
+relative-time-years-ago = { $count ->
+    [one] 1 year ago
+   *[other] { $count } years ago
+}
+relative-time-in-minutes = { $count ->
+    [one] in 1 minute
+   *[other] in { $count } minutes
+}
+relative-time-in-hours = { $count ->
+    [one] in 1 hour
+   *[other] in { $count } hours
+}
+relative-time-in-days = { $count ->
+    [one] in 1 day
+   *[other] in { $count } days
+}
+relative-time-in-months = { $count ->
+    [one] in 1 month
+   *[other] in { $count } months
+}
+relative-time-in-years = { $count ->
+    [one] in 1 year
+   *[other] in { $count } years
+}
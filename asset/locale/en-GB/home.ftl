@@ -0,0 +1,5 @@
+# Home
+home-title = Rust Vue Exercise
+home-intro = This is Rust Vue Exercise.
+home-exercise = Exercise { $number }
+home-count = Count is:
@@ -0,0 +1,9 @@
+# Language
+language-id = en-GB
+language-name = English (UK)
+
+# Formatting, chrono strftime patterns
+format-date = %d/%m/%Y
+format-datetime = %d/%m/%Y %H:%M
+format-number-decimal = .
+format-number-group = ,
@@ -0,0 +1,24 @@
+# User page
+user-title = User: { $username }
+user-visitor-title = Visitor
+user-welcome = Welcome to the user page!
+user-logged-in-as = You are logged in as a user '{ $username }'.
+user-logout-hint = You can log out by clicking the button below.
+user-logout = Log out
+user-visitor-logged-in = You are logged in as a visitor.
+user-visitor-login-hint = You can log in as a user by clicking the button below.
+user-login-as-user = Log in as a user
+user-current-time = It is { $time } ({ $timeZone }).
+user-time-zone = Time zone
+user-time-zone-utc = UTC (default)
+user-time-zone-submit = Save time zone
+
+# Login and register
+user-login-title = Login
+user-login-submit = Login
+user-register-hint = If you don't have an account, you can register by clicking the button below.
+user-register-title = Register
+user-register-submit = Register
+user-username = Username
+user-password = Password
+user-password-confirm = Confirm password
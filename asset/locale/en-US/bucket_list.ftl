@@ -0,0 +1 @@
+../en-GB/bucket_list.ftl
\ No newline at end of file
@@ -0,0 +1 @@
+../en-GB/common.ftl
\ No newline at end of file
@@ -0,0 +1 @@
+../en-GB/home.ftl
\ No newline at end of file
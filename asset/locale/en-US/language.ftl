@@ -0,0 +1,9 @@
+# Language
+language-id = en-US
+language-name = English (US)
+
+# Formatting, chrono strftime patterns
+format-date = %m/%d/%Y
+format-datetime = %m/%d/%Y %-I:%M %p
+format-number-decimal = .
+format-number-group = ,
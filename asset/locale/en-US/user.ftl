@@ -0,0 +1 @@
+../en-GB/user.ftl
\ No newline at end of file
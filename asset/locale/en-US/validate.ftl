@@ -0,0 +1 @@
+../en-GB/validate.ftl
\ No newline at end of file
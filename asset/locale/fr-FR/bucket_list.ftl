@@ -0,0 +1,8 @@
+# Bucket list
+bucket-list-title = Liste de souhaits
+bucket-list-id = ID
+bucket-list-name = Nom
+bucket-list-description = Description
+bucket-list-timestamp = Date
+bucket-list-add = Ajouter
+flash-bucket-added = « { $name } » a été ajouté
@@ -0,0 +1,74 @@
+# Layout
+site-name = Un Petit Poème
+page-untitled = Sans titre
+skip-to-content = Aller au contenu
+
+# Navigation
+nav-label = Navigation principale
+nav-home = Accueil
+nav-bucket-list = Liste de souhaits
+nav-user = Utilisateur
+nav-hello-user = Bonjour, { $username }
+nav-visitor-login = Vous êtes un visiteur, cliquez ici pour vous connecter
+nav-language = Langue
+nav-language-auto = Langue du navigateur
+nav-language-submit = Changer
+nav-theme = Thème
+nav-theme-system = Système
+nav-theme-light = Clair
+nav-theme-dark = Sombre
+nav-theme-submit = Appliquer
+
+# Flash messages
+flash-login-succeeded = Connexion réussie
+flash-login-failed = Échec de la connexion
+flash-logout-succeeded = Déconnexion réussie
+flash-register-succeeded = Inscription réussie
+flash-register-failed = Échec de l'inscription
+flash-language-failed = La langue n'a pas pu être enregistrée
+flash-time-zone-saved = Fuseau horaire enregistré
+flash-time-zone-failed = Le fuseau horaire n'a pas pu être enregistré
+flash-dismiss = Fermer
+
+# Relative time
+relative-time-now = à l'instant
+relative-time-minutes-ago = { $count ->
+    [one] il y a 1 minute
+   *[other] il y a { $count } minutes
+}
+relative-time-hours-ago = { $count ->
+    [one] il y a 1 heure
+   *[other] il y a { $count } heures
+}
+relative-time-days-ago = { $count ->
+    [one] il y a 1 jour
+   *[other] il y a { $count } jours
+}
+relative-time-months-ago = { $count ->
+    [one] il y a 1 mois
+   *[other] il y a { $count } mois
+}
+relative-time-years-ago = { $count ->
+    [one] il y a 1 an
+   *[other] il y a { $count } ans
+}
+relative-time-in-minutes = { $count ->
+    [one] dans 1 minute
+   *[other] dans { $count } minutes
+}
+relative-time-in-hours = { $count ->
+    [one] dans 1 heure
+   *[other] dans { $count } heures
+}
+relative-time-in-days = { $count ->
+    [one] dans 1 jour
+   *[other] dans { $count } jours
+}
+relative-time-in-months = { $count ->
+    [one] dans 1 mois
+   *[other] dans { $count } mois
+}
+relative-time-in-years = { $count ->
+    [one] dans 1 an
+   *[other] dans { $count } ans
+}
@@ -0,0 +1,5 @@
+# Home
+home-title = Exercice Rust Vue
+home-intro = Ceci est l'exercice Rust Vue.
+home-exercise = Exercice { $number }
+home-count = Le compteur est à :
@@ -0,0 +1,24 @@
+# User page
+user-title = Utilisateur : { $username }
+user-visitor-title = Visiteur
+user-welcome = Bienvenue sur la page utilisateur !
+user-logged-in-as = Vous êtes connecté en tant qu'utilisateur « { $username } ».
+user-logout-hint = Vous pouvez vous déconnecter en cliquant sur le bouton ci-dessous.
+user-logout = Se déconnecter
+user-visitor-logged-in = Vous êtes connecté en tant que visiteur.
+user-visitor-login-hint = Vous pouvez vous connecter en tant qu'utilisateur en cliquant sur le bouton ci-dessous.
+user-login-as-user = Se connecter en tant qu'utilisateur
+user-current-time = Il est { $time } ({ $timeZone }).
+user-time-zone = Fuseau horaire
+user-time-zone-utc = UTC (par défaut)
+user-time-zone-submit = Enregistrer le fuseau horaire
+
+# Login and register
+user-login-title = Connexion
+user-login-submit = Se connecter
+user-register-hint = Si vous n'avez pas de compte, vous pouvez vous inscrire en cliquant sur le bouton ci-dessous.
+user-register-title = Inscription
+user-register-submit = S'inscrire
+user-username = Nom d'utilisateur
+user-password = Mot de passe
+user-password-confirm = Confirmer le mot de passe
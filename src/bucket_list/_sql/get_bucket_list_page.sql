@@ -0,0 +1,5 @@
+SELECT id, name, description, timestamp
+FROM bucket_list
+WHERE id < :before
+ORDER BY id DESC
+LIMIT :limit;
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO bucket_list (name, description)
+VALUES ($1, $2);
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT id, name, description, timestamp
+FROM bucket_list
+ORDER BY timestamp DESC;
\ No newline at end of file
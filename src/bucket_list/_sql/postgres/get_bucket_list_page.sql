@@ -0,0 +1,5 @@
+SELECT id, name, description, timestamp
+FROM bucket_list
+WHERE id < $1
+ORDER BY id DESC
+LIMIT $2;
\ No newline at end of file
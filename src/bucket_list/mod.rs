@@ -2,3 +2,4 @@ pub mod model;
 pub mod repository;
 pub mod route;
 pub(self) mod rules;
+pub mod transfer;
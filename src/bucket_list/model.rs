@@ -1,6 +1,8 @@
 use crate::bucket_list::rules::{DescriptionBucketRulesExt, NameBucketRulesExt};
 use crate::common::locale::LocaleExtForResult;
+use crate::common::locale::format::LocaleExtForFormat;
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use cjtoolkit_structured_validator::common::flag_error::FlagCounter;
 use cjtoolkit_structured_validator::types::description::{Description, DescriptionError};
 use cjtoolkit_structured_validator::types::name::{Name, NameError};
@@ -16,6 +18,25 @@ pub struct BucketListItem {
     pub timestamp: DateTime<Utc>,
 }
 
+/// An item as the page shows it, with the timestamp formatted for the reader.
+#[derive(Serialize, Debug)]
+pub struct BucketListItemView {
+    #[serde(flatten)]
+    pub item: BucketListItem,
+    pub timestamp_display: String,
+    pub timestamp_relative: String,
+}
+
+impl BucketListItemView {
+    pub fn new(item: BucketListItem, locale: &Locale, time_zone: Tz, now: &DateTime<Utc>) -> Self {
+        Self {
+            timestamp_display: locale.format_datetime(&item.timestamp, time_zone),
+            timestamp_relative: locale.format_relative(&item.timestamp, now),
+            item,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AddToBucketList {
     pub name: String,
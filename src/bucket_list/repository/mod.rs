@@ -0,0 +1,146 @@
+use crate::bucket_list::model::{AddToBucketListValidated, BucketListItem};
+use crate::common::config::Config;
+use crate::common::config::database::DatabaseBackend;
+use crate::common::context::{Context, ContextError, FromContext};
+use crate::common::db::SqliteClient;
+use async_trait::async_trait;
+use error_stack::{Report, ResultExt};
+use poem::error::ResponseError;
+use poem::http::StatusCode;
+use sqlite::SqliteBucketListRepository;
+use thiserror::Error;
+
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+
+#[derive(Error, Debug)]
+pub enum BucketListRepositoryError {
+    #[error("Query error")]
+    QueryError,
+    #[error("Row Value error")]
+    RowValueError,
+    #[error("Lock error")]
+    LockError,
+    #[error("Connection error")]
+    ConnectionError,
+}
+
+impl ResponseError for BucketListRepositoryError {
+    fn status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+#[async_trait]
+pub trait BucketListRepository: Send + Sync {
+    /// Newest first.
+    async fn get_all_from_bucket_list(
+        &self,
+    ) -> Result<Box<[BucketListItem]>, Report<BucketListRepositoryError>>;
+
+    /// Up to `limit` items with an id below `before`, highest id first, for paging through
+    /// the whole list without loading it at once.
+    async fn get_bucket_list_page(
+        &self,
+        before: i64,
+        limit: usize,
+    ) -> Result<Box<[BucketListItem]>, Report<BucketListRepositoryError>>;
+
+    async fn add_to_bucket_list(
+        &self,
+        add_to_bucket_list: &AddToBucketListValidated,
+    ) -> Result<(), Report<BucketListRepositoryError>>;
+
+    /// Adds every item in one transaction, so a failed insert leaves the list untouched.
+    async fn import_to_bucket_list(
+        &self,
+        items: &[AddToBucketListValidated],
+    ) -> Result<usize, Report<BucketListRepositoryError>>;
+}
+
+/// The repository for the configured `database.backend`.
+pub async fn bucket_list_repository(
+    config: &Config,
+) -> Result<Box<dyn BucketListRepository>, Report<BucketListRepositoryError>> {
+    match config.database.backend {
+        DatabaseBackend::Sqlite => Ok(Box::new(SqliteBucketListRepository::new(
+            SqliteClient::fetch(config)
+                .await
+                .change_context(BucketListRepositoryError::ConnectionError)?,
+        ))),
+        #[cfg(feature = "postgres")]
+        DatabaseBackend::Postgres => Ok(Box::new(postgres::PostgresBucketListRepository::new(
+            crate::common::db::postgres::PostgresClient::fetch(config)
+                .await
+                .change_context(BucketListRepositoryError::ConnectionError)?,
+        ))),
+        #[cfg(not(feature = "postgres"))]
+        DatabaseBackend::Postgres => Err(Report::new(BucketListRepositoryError::ConnectionError)),
+    }
+}
+
+impl<'a> FromContext for Box<dyn BucketListRepository + 'a> {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        let repository: Box<dyn BucketListRepository + 'a> = bucket_list_repository(&ctx.config)
+            .await
+            .change_context(ContextError::Other)?;
+        Ok(repository)
+    }
+}
+
+/// A repository on a new database for each backend under test: in-memory SQLite, and
+/// Postgres when built with it and `LITTLE_POEM_TEST_DATABASE__URL` is set.
+#[cfg(test)]
+pub async fn test_repositories() -> Vec<Box<dyn BucketListRepository>> {
+    let sqlite: Box<dyn BucketListRepository> = Box::new(SqliteBucketListRepository::new(
+        SqliteClient::new(":memory:".to_string()).unwrap(),
+    ));
+    #[cfg(feature = "postgres")]
+    let postgres = crate::common::db::postgres::test_client()
+        .await
+        .map(|client| Box::new(postgres::PostgresBucketListRepository::new(client)) as _);
+    #[cfg(not(feature = "postgres"))]
+    let postgres = None;
+    std::iter::once(sqlite).chain(postgres).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bucket_list::model::{AddToBucketList, AddToBucketListResult};
+
+    fn validated(name: &str, description: &str) -> AddToBucketListValidated {
+        let result: AddToBucketListResult = (&AddToBucketList {
+            name: name.to_string(),
+            description: description.to_string(),
+        })
+            .into();
+        result.0.ok().unwrap()
+    }
+
+    #[tokio::test]
+    async fn added_and_imported_items_are_listed() {
+        for repo in test_repositories().await {
+            repo.add_to_bucket_list(&validated("Kayaking", "Paddle down the Wye"))
+                .await
+                .unwrap();
+            let imported = repo
+                .import_to_bucket_list(&[
+                    validated("Skydiving", "Jump over the coast"),
+                    validated("Marathon", "Run all of it"),
+                ])
+                .await
+                .unwrap();
+            assert_eq!(imported, 2);
+
+            // A new database starts with an example item.
+            let items = repo.get_all_from_bucket_list().await.unwrap();
+            let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+            assert_eq!(names.len(), 4);
+            for name in ["Kayaking", "Marathon", "Skydiving"] {
+                assert!(names.contains(&name));
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::bucket_list::model::{AddToBucketListValidated, BucketListItem};
+use crate::bucket_list::repository::{BucketListRepository, BucketListRepositoryError};
+use crate::common::db::postgres::PostgresClient;
+use async_trait::async_trait;
+use error_stack::{Report, ResultExt};
+
+pub struct PostgresBucketListRepository {
+    postgres_client: PostgresClient,
+}
+
+impl PostgresBucketListRepository {
+    pub fn new(postgres_client: PostgresClient) -> Self {
+        Self { postgres_client }
+    }
+}
+
+#[async_trait]
+impl BucketListRepository for PostgresBucketListRepository {
+    async fn get_all_from_bucket_list(
+        &self,
+    ) -> Result<Box<[BucketListItem]>, Report<BucketListRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        let rows = client
+            .query(
+                include_str!("../_sql/postgres/get_all_from_bucket_list.sql"),
+                &[],
+            )
+            .await
+            .change_context(BucketListRepositoryError::QueryError)?;
+
+        let mut items: Vec<BucketListItem> = Vec::with_capacity(rows.len());
+        for row in rows {
+            items.push(BucketListItem {
+                id: row
+                    .try_get("id")
+                    .change_context(BucketListRepositoryError::RowValueError)?,
+                name: row
+                    .try_get("name")
+                    .change_context(BucketListRepositoryError::RowValueError)?,
+                description: row
+                    .try_get("description")
+                    .change_context(BucketListRepositoryError::RowValueError)?,
+                timestamp: row
+                    .try_get("timestamp")
+                    .change_context(BucketListRepositoryError::RowValueError)?,
+            });
+        }
+
+        Ok(items.into())
+    }
+
+    async fn get_bucket_list_page(
+        &self,
+        before: i64,
+        limit: usize,
+    ) -> Result<Box<[BucketListItem]>, Report<BucketListRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        let rows = client
+            .query(
+                include_str!("../_sql/postgres/get_bucket_list_page.sql"),
+                &[&before, &(limit as i64)],
+            )
+            .await
+            .change_context(BucketListRepositoryError::QueryError)?;
+
+        let mut items: Vec<BucketListItem> = Vec::with_capacity(rows.len());
+        for row in rows {
+            items.push(BucketListItem {
+                id: row
+                    .try_get("id")
+                    .change_context(BucketListRepositoryError::RowValueError)?,
+                name: row
+                    .try_get("name")
+                    .change_context(BucketListRepositoryError::RowValueError)?,
+                description: row
+                    .try_get("description")
+                    .change_context(BucketListRepositoryError::RowValueError)?,
+                timestamp: row
+                    .try_get("timestamp")
+                    .change_context(BucketListRepositoryError::RowValueError)?,
+            });
+        }
+
+        Ok(items.into())
+    }
+
+    async fn add_to_bucket_list(
+        &self,
+        add_to_bucket_list: &AddToBucketListValidated,
+    ) -> Result<(), Report<BucketListRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        client
+            .execute(
+                include_str!("../_sql/postgres/add_to_bucket_list.sql"),
+                &[
+                    &add_to_bucket_list.name.as_str(),
+                    &add_to_bucket_list.description.as_str(),
+                ],
+            )
+            .await
+            .change_context(BucketListRepositoryError::QueryError)?;
+        Ok(())
+    }
+
+    async fn import_to_bucket_list(
+        &self,
+        items: &[AddToBucketListValidated],
+    ) -> Result<usize, Report<BucketListRepositoryError>> {
+        let mut client = self.postgres_client.get_client_mut().await;
+        let tx = client
+            .transaction()
+            .await
+            .change_context(BucketListRepositoryError::QueryError)?;
+        let stmt = tx
+            .prepare(include_str!("../_sql/postgres/add_to_bucket_list.sql"))
+            .await
+            .change_context(BucketListRepositoryError::QueryError)?;
+        for item in items {
+            tx.execute(&stmt, &[&item.name.as_str(), &item.description.as_str()])
+                .await
+                .change_context(BucketListRepositoryError::QueryError)?;
+        }
+        tx.commit()
+            .await
+            .change_context(BucketListRepositoryError::QueryError)?;
+
+        Ok(items.len())
+    }
+}
@@ -0,0 +1,143 @@
+use crate::bucket_list::model::{AddToBucketListValidated, BucketListItem};
+use crate::bucket_list::repository::{BucketListRepository, BucketListRepositoryError};
+use crate::common::db::{SqliteClient, SqliteClientError, SqliteConnection};
+use crate::common::metrics::Metrics;
+use async_trait::async_trait;
+use error_stack::{Report, ResultExt};
+use rusqlite::named_params;
+use std::time::Instant;
+
+pub struct SqliteBucketListRepository {
+    sqlite_client: SqliteClient,
+}
+
+impl SqliteBucketListRepository {
+    pub fn new(sqlite_client: SqliteClient) -> Self {
+        Self { sqlite_client }
+    }
+
+    fn borrow_conn(&'_ self) -> Result<SqliteConnection<'_>, Report<BucketListRepositoryError>> {
+        let start = Instant::now();
+        let guard = self.sqlite_client.lock().map_err(|err| {
+            let context = match err.current_context() {
+                SqliteClientError::Closed => BucketListRepositoryError::ConnectionError,
+                _ => BucketListRepositoryError::LockError,
+            };
+            err.change_context(context)
+        })?;
+        Metrics::get().record_sqlite_lock_wait(start.elapsed());
+        Ok(guard)
+    }
+}
+
+#[async_trait]
+impl BucketListRepository for SqliteBucketListRepository {
+    async fn get_all_from_bucket_list(
+        &self,
+    ) -> Result<Box<[BucketListItem]>, Report<BucketListRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        let mut stmt = conn
+            .prepare_cached(include_str!("../_sql/get_all_from_bucket_list.sql"))
+            .change_context(BucketListRepositoryError::QueryError)?;
+
+        let item_iter = stmt
+            .query_map([], |row| {
+                Ok(BucketListItem {
+                    id: row.get("id")?,
+                    name: row.get("name")?,
+                    description: row.get("description")?,
+                    timestamp: row.get("timestamp")?,
+                })
+            })
+            .change_context(BucketListRepositoryError::RowValueError)?;
+
+        let mut items: Vec<BucketListItem> = Vec::new();
+        for item in item_iter {
+            items.push(item.change_context(BucketListRepositoryError::RowValueError)?);
+        }
+
+        Ok(items.into())
+    }
+
+    async fn get_bucket_list_page(
+        &self,
+        before: i64,
+        limit: usize,
+    ) -> Result<Box<[BucketListItem]>, Report<BucketListRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        let mut stmt = conn
+            .prepare_cached(include_str!("../_sql/get_bucket_list_page.sql"))
+            .change_context(BucketListRepositoryError::QueryError)?;
+
+        let item_iter = stmt
+            .query_map(
+                named_params! {
+                    ":before": before,
+                    ":limit": limit as i64,
+                },
+                |row| {
+                    Ok(BucketListItem {
+                        id: row.get("id")?,
+                        name: row.get("name")?,
+                        description: row.get("description")?,
+                        timestamp: row.get("timestamp")?,
+                    })
+                },
+            )
+            .change_context(BucketListRepositoryError::RowValueError)?;
+
+        let mut items: Vec<BucketListItem> = Vec::new();
+        for item in item_iter {
+            items.push(item.change_context(BucketListRepositoryError::RowValueError)?);
+        }
+
+        Ok(items.into())
+    }
+
+    async fn add_to_bucket_list(
+        &self,
+        add_to_bucket_list: &AddToBucketListValidated,
+    ) -> Result<(), Report<BucketListRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        conn.execute(
+            include_str!("../_sql/add_to_bucket_list.sql"),
+            named_params! {
+                ":name": add_to_bucket_list.name.as_str(),
+                ":description": add_to_bucket_list.description.as_str(),
+            },
+        )
+        .change_context(BucketListRepositoryError::QueryError)?;
+
+        Ok(())
+    }
+
+    async fn import_to_bucket_list(
+        &self,
+        items: &[AddToBucketListValidated],
+    ) -> Result<usize, Report<BucketListRepositoryError>> {
+        let mut conn = self.borrow_conn()?;
+
+        let tx = conn
+            .transaction()
+            .change_context(BucketListRepositoryError::QueryError)?;
+        {
+            let mut stmt = tx
+                .prepare_cached(include_str!("../_sql/add_to_bucket_list.sql"))
+                .change_context(BucketListRepositoryError::QueryError)?;
+            for item in items {
+                stmt.execute(named_params! {
+                    ":name": item.name.as_str(),
+                    ":description": item.description.as_str(),
+                })
+                .change_context(BucketListRepositoryError::QueryError)?;
+            }
+        }
+        tx.commit()
+            .change_context(BucketListRepositoryError::QueryError)?;
+
+        Ok(items.len())
+    }
+}
@@ -1,91 +1,112 @@
 use crate::bucket_list::model::{
-    AddToBucketList, AddToBucketListResult, AddToBucketListValidationErrorResponse, BucketListItem,
+    AddToBucketList, AddToBucketListResult, AddToBucketListValidationErrorResponse,
+    BucketListItemView,
 };
 use crate::bucket_list::repository::{BucketListRepository, BucketListRepositoryError};
+use crate::bucket_list::transfer::{TransferError, TransferFormat, page_items, validate_rows};
 use crate::common::adapter::{ReportAdapter, ResultAdapter};
 use crate::common::context::Dep;
 use crate::common::context::user::JustDep;
 use crate::common::csrf::CsrfHeaderChecker;
 use crate::common::embed::{Asset, EmbedAsString};
 use crate::common::error::{ErrorReportResponse, JsonErrorOutput};
+use crate::common::flash::{Flash, FlashMessage};
 use crate::common::html::context_html::ContextHtmlBuilder;
-use crate::common::icon::plus_icon;
-use maud::{Markup, PreEscaped, html};
+use crate::common::html::navigation::{NavigationItem, register_navigation};
+use crate::common::icon::{list_icon, plus_icon};
+use crate::common::locale::LocaleExtForText;
+use chrono::Utc;
+use chrono_tz::Tz;
+use maud::{Markup, html};
 use poem::http::StatusCode;
+use poem::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
 use poem::i18n::Locale;
-use poem::web::{Json, WithStatus};
-use poem::{IntoResponse, Response, Route, get, handler, post};
+use poem::middleware::SizeLimit;
+use poem::session::Session;
+use poem::web::{Json, Query, WithStatus};
+use poem::{Body, EndpointExt, IntoResponse, Request, Response, Route, get, handler, post};
+use serde::Deserialize;
 use serde_json::{Value, json};
 
 pub const BUCKET_LIST_PATH: &'static str = "/bucket-list/";
 
+/// Largest import body, the request must also send `Content-Length`.
+const IMPORT_SIZE_LIMIT: usize = 1024 * 1024;
+
 #[handler]
 async fn main_bucket_list(JustDep(context_html_builder, _): JustDep<ContextHtmlBuilder>) -> Markup {
-    let title = "Bucket List";
+    let locale = &context_html_builder.locale;
+    let title = locale.translate("bucket-list-title");
     context_html_builder
-        .attach_title(title)
+        .attach_title(title.as_str())
         .set_current_tag("bucket-list")
         .attach_content(html! {
             h1 .mt-3 { (title) }
             div #bucket-list .mt-3 v-cloak {
                 div .bucket-list-header {
-                    span .bucket-list-col { "ID" }
-                    span .bucket-list-col { "Name" }
-                    span .bucket-list-col { "Description" }
-                    span .bucket-list-col { "Timestamp" }
+                    span .bucket-list-col { (locale.translate("bucket-list-id")) }
+                    span .bucket-list-col { (locale.translate("bucket-list-name")) }
+                    span .bucket-list-col { (locale.translate("bucket-list-description")) }
+                    span .bucket-list-col { (locale.translate("bucket-list-timestamp")) }
                 }
                 div .bucket-list-item "v-for"="item in bucket_list" {
                     span .bucket-list-col { "{{ item.id }}" }
                     span .bucket-list-col { "{{ item.name }}" }
                     span .bucket-list-col { "{{ item.description }}" }
-                    span .bucket-list-col { "{{ item.timestamp }}" }
+                    span .bucket-list-col ":title"="item.timestamp_display" { "{{ item.timestamp_relative }}" }
                 }
                 div .bucket-form .mt-5 {
-                    input .bucket-list-col .bucket-form-input
-                        type="text" placeholder="Name" "v-model"="input_name";
-                    input .bucket-list-col .bucket-form-input
-                        type="text" placeholder="Description" "v-model"="input_description";
-                    button .bucket-list-col .btn .btn-sky-blue "v-on:click"="addToBucketList" {
-                        "Add"
-                        (plus_icon())
-                    }
-                }
-                div .bucket-form-error "v-if"="error" {
-                    span .bucket-list-col {
-                        ul {
-                            li "v-for"="message in error.name" { "{{ message }}" }
+                    div .form-field .bucket-list-col {
+                        label .form-label for="bucket-add-name" { (locale.translate("bucket-list-name")) }
+                        input #bucket-add-name type="text" "v-model"="input_name"
+                            ":aria-invalid"="error.name?.length ? 'true' : null"
+                            ":aria-describedby"="error.name?.length ? 'bucket-add-name-errors' : null";
+                        ul .validation-error-list #bucket-add-name-errors "v-if"="error.name?.length" {
+                            li .validation-error-message "v-for"="message in error.name" { "{{ message }}" }
                         }
                     }
-                    span .bucket-list-col {
-                        ul {
-                            li "v-for"="message in error.description" { "{{ message }}" }
+                    div .form-field .bucket-list-col {
+                        label .form-label for="bucket-add-description" { (locale.translate("bucket-list-description")) }
+                        input #bucket-add-description type="text" "v-model"="input_description"
+                            ":aria-invalid"="error.description?.length ? 'true' : null"
+                            ":aria-describedby"="error.description?.length ? 'bucket-add-description-errors' : null";
+                        ul .validation-error-list #bucket-add-description-errors "v-if"="error.description?.length" {
+                            li .validation-error-message "v-for"="message in error.description" { "{{ message }}" }
                         }
                     }
-                    span .bucket-list-col {}
+                    button .bucket-list-col .btn .btn-sky-blue "v-on:click"="addToBucketList" {
+                        (locale.translate("bucket-list-add"))
+                        (plus_icon())
+                    }
                 }
             }
         })
-        .attach_footer(get_bucket_list_js())
+        .attach_script(get_bucket_list_js())
         .build()
 }
 
-fn get_bucket_list_js() -> Markup {
-    let js = if cfg!(debug_assertions) {
+fn get_bucket_list_js() -> String {
+    if cfg!(debug_assertions) {
         Asset::get("js/bucket_list.js").as_string()
     } else {
         Asset::get("js/bucket_list.min.js").as_string()
-    };
-    html! {
-        script type="module" { (PreEscaped(js)) }
     }
 }
 
 #[handler]
 async fn all_bucket_list(
-    Dep(repo): Dep<BucketListRepository>,
-) -> ReportAdapter<Json<Box<[BucketListItem]>>, BucketListRepositoryError, JsonErrorOutput> {
+    Dep(repo): Dep<Box<dyn BucketListRepository>>,
+    JustDep(locale, _): JustDep<Locale>,
+    JustDep(time_zone, _): JustDep<Tz>,
+) -> ReportAdapter<Json<Box<[BucketListItemView]>>, BucketListRepositoryError, JsonErrorOutput> {
     ReportAdapter::execute(async {
-        let items = repo.get_all_from_bucket_list()?;
+        let now = Utc::now();
+        let items = repo
+            .get_all_from_bucket_list()
+            .await?
+            .into_iter()
+            .map(|item| BucketListItemView::new(item, &locale, time_zone, &now))
+            .collect();
         Ok(Json(items))
     })
     .await
@@ -109,10 +130,11 @@ impl IntoResponse for AddBucketListRouteError {
 
 #[handler]
 async fn add_bucket_list(
-    Dep(repo): Dep<BucketListRepository>,
+    Dep(repo): Dep<Box<dyn BucketListRepository>>,
     Json(data): Json<AddToBucketList>,
     _csrf_header_checker: CsrfHeaderChecker,
-    locale: Locale,
+    JustDep(locale, _): JustDep<Locale>,
+    session: &Session,
 ) -> ResultAdapter<WithStatus<Json<Value>>, AddBucketListRouteError> {
     ResultAdapter::execute(async {
         let AddToBucketListResult(data) = (&data).into();
@@ -120,16 +142,103 @@ async fn add_bucket_list(
             data.map_err(|e| AddBucketListRouteError::Validate(Json((e, &locale).into())))?;
 
         repo.add_to_bucket_list(&data)
+            .await
             .map_err(|e| AddBucketListRouteError::Repo(ErrorReportResponse::new(e)))?;
+        session.flash(Flash::success("flash-bucket-added").attach_arg("name", data.name.as_str()));
 
         Ok(Json(json!({"message": "Success"})).with_status(StatusCode::CREATED))
     })
     .await
 }
 
+#[derive(Deserialize)]
+struct TransferQuery {
+    format: Option<TransferFormat>,
+}
+
+#[handler]
+async fn export_bucket_list(
+    Dep(repo): Dep<Box<dyn BucketListRepository>>,
+    Query(query): Query<TransferQuery>,
+) -> Response {
+    let format = query.format.unwrap_or_default();
+    Response::builder()
+        .content_type(format.content_type())
+        .header(
+            CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"bucket-list.{}\"",
+                format.extension()
+            ),
+        )
+        .body(Body::from_bytes_stream(format.encode(page_items(repo))))
+}
+
+enum ImportBucketListRouteError {
+    Repo(ErrorReportResponse<BucketListRepositoryError, JsonErrorOutput>),
+    Transfer(ErrorReportResponse<TransferError, JsonErrorOutput>),
+}
+
+impl IntoResponse for ImportBucketListRouteError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Repo(err) => err.into_response(),
+            Self::Transfer(err) => err.into_response(),
+        }
+    }
+}
+
+/// Nothing is inserted unless every row is valid, the response lists the rejected rows.
+#[handler]
+async fn import_bucket_list(
+    Dep(repo): Dep<Box<dyn BucketListRepository>>,
+    Query(query): Query<TransferQuery>,
+    req: &Request,
+    body: Vec<u8>,
+    _csrf_header_checker: CsrfHeaderChecker,
+    JustDep(locale, _): JustDep<Locale>,
+) -> ResultAdapter<WithStatus<Json<Value>>, ImportBucketListRouteError> {
+    ResultAdapter::execute(async {
+        let format = query
+            .format
+            .or_else(|| {
+                req.header(CONTENT_TYPE)
+                    .and_then(TransferFormat::from_content_type)
+            })
+            .unwrap_or_default();
+        let items = format
+            .decode(&body)
+            .map_err(|e| ImportBucketListRouteError::Transfer(ErrorReportResponse::new(e)))?;
+
+        let (valid, errors) = validate_rows(&items, Some(&locale));
+        if !errors.is_empty() {
+            return Ok(Json(json!({"imported": 0, "errors": errors}))
+                .with_status(StatusCode::UNPROCESSABLE_ENTITY));
+        }
+        let imported = repo
+            .import_to_bucket_list(&valid)
+            .await
+            .map_err(|e| ImportBucketListRouteError::Repo(ErrorReportResponse::new(e)))?;
+
+        Ok(Json(json!({"imported": imported, "errors": errors})).with_status(StatusCode::CREATED))
+    })
+    .await
+}
+
 pub fn route_bucket_list() -> Route {
+    register_navigation(
+        NavigationItem::new("nav-bucket-list", BUCKET_LIST_PATH, "bucket-list")
+            .set_order(200)
+            .attach_icon(list_icon),
+    );
+
     Route::new()
         .at("/", get(main_bucket_list))
         .at("/all", get(all_bucket_list))
         .at("/add", post(add_bucket_list))
+        .at("/export", get(export_bucket_list))
+        .at(
+            "/import",
+            post(import_bucket_list).with(SizeLimit::new(IMPORT_SIZE_LIMIT)),
+        )
 }
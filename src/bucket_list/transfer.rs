@@ -0,0 +1,201 @@
+use crate::bucket_list::model::{
+    AddToBucketList, AddToBucketListResult, AddToBucketListValidated,
+    AddToBucketListValidationErrorResponse, BucketListItem,
+};
+use crate::bucket_list::repository::{BucketListRepository, BucketListRepositoryError};
+use crate::common::error::ExtraResultExt;
+use error_stack::{Report, ResultExt};
+use futures_util::stream::{self, Stream, StreamExt, TryStreamExt};
+use poem::error::ResponseError;
+use poem::http::StatusCode;
+use poem::i18n::Locale;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+const EXPORT_PAGE_SIZE: usize = 500;
+
+#[derive(Error, Debug)]
+pub enum TransferError {
+    #[error("Invalid JSON")]
+    Json,
+    #[error("Invalid CSV")]
+    Csv,
+}
+
+impl ResponseError for TransferError {
+    fn status(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+#[derive(Deserialize, clap::ValueEnum, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+impl TransferFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+        }
+    }
+
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        match mime.to_ascii_lowercase().as_str() {
+            "application/json" => Some(Self::Json),
+            "text/csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    fn encode_item(&self, item: &BucketListItem, first: bool) -> Vec<u8> {
+        match self {
+            Self::Json => {
+                let mut chunk = if first {
+                    b"\n  ".to_vec()
+                } else {
+                    b",\n  ".to_vec()
+                };
+                chunk.extend(serde_json::to_vec(item).unwrap_or_default());
+                chunk
+            }
+            Self::Csv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(Vec::new());
+                let _ = writer.serialize(item);
+                writer.into_inner().unwrap_or_default()
+            }
+        }
+    }
+
+    /// Encodes one item per chunk, a failed read ends the stream with an error.
+    pub fn encode<S>(self, items: S) -> impl Stream<Item = Result<Vec<u8>, std::io::Error>> + Send
+    where
+        S: Stream<Item = Result<BucketListItem, Report<BucketListRepositoryError>>> + Send,
+    {
+        let (open, close) = match self {
+            Self::Json => (b"[".to_vec(), b"\n]\n".to_vec()),
+            Self::Csv => (b"id,name,description,timestamp\n".to_vec(), Vec::new()),
+        };
+        let rows = items.enumerate().map(move |(i, item)| {
+            item.map(|item| self.encode_item(&item, i == 0))
+                .map_err(|err| std::io::Error::other(format!("{:?}", err)))
+        });
+
+        stream::once(async { Ok(open) })
+            .chain(rows)
+            .chain(stream::once(async { Ok(close) }))
+    }
+
+    /// Extra columns or fields, such as `id` and `timestamp` from an export, are ignored.
+    pub fn decode(&self, data: &[u8]) -> Result<Vec<AddToBucketList>, Report<TransferError>> {
+        match self {
+            Self::Json => serde_json::from_slice(data)
+                .change_context(TransferError::Json)
+                .attach_critical("Expected an array of {name, description}".to_string()),
+            Self::Csv => {
+                let mut reader = csv::Reader::from_reader(data);
+                let mut items = Vec::new();
+                for (row, item) in reader.deserialize::<AddToBucketList>().enumerate() {
+                    items.push(
+                        item.change_context(TransferError::Csv)
+                            .attach_critical_lazy(|| format!("Row {} is malformed", row + 1))?,
+                    );
+                }
+                Ok(items)
+            }
+        }
+    }
+}
+
+/// Every item, highest id first, read a page at a time so exports of large lists
+/// do not hold them in memory.
+pub fn page_items(
+    repo: Box<dyn BucketListRepository>,
+) -> impl Stream<Item = Result<BucketListItem, Report<BucketListRepositoryError>>> + Send {
+    stream::try_unfold((repo, Some(i64::MAX)), |(repo, before)| async move {
+        let Some(before) = before else {
+            return Ok::<_, Report<BucketListRepositoryError>>(None);
+        };
+        let page = repo.get_bucket_list_page(before, EXPORT_PAGE_SIZE).await?;
+        let next = match page.last() {
+            Some(item) if page.len() == EXPORT_PAGE_SIZE => Some(item.id),
+            _ => None,
+        };
+        let items = stream::iter(page.into_vec().into_iter().map(Ok));
+        Ok(Some((items, (repo, next))))
+    })
+    .try_flatten()
+}
+
+#[derive(Serialize, Debug)]
+pub struct ImportRowError {
+    /// 1-based, not counting the CSV header.
+    pub row: usize,
+    #[serde(flatten)]
+    pub errors: AddToBucketListValidationErrorResponse,
+}
+
+/// Splits rows into the ones ready to insert and the ones that failed validation,
+/// messages are translated when a locale is given.
+pub fn validate_rows(
+    items: &[AddToBucketList],
+    locale: Option<&Locale>,
+) -> (Vec<AddToBucketListValidated>, Vec<ImportRowError>) {
+    let mut valid = Vec::with_capacity(items.len());
+    let mut errors = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        let AddToBucketListResult(result) = item.into();
+        match result {
+            Ok(item) => valid.push(item),
+            Err(err) => errors.push(ImportRowError {
+                row: i + 1,
+                errors: match locale {
+                    Some(locale) => (err, locale).into(),
+                    None => err.into(),
+                },
+            }),
+        }
+    }
+    (valid, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bucket_list::repository::test_repositories;
+
+    #[tokio::test]
+    async fn export_pages_through_every_item() {
+        let items: Vec<AddToBucketList> = (0..EXPORT_PAGE_SIZE + 10)
+            .map(|i| AddToBucketList {
+                name: format!("Item {}", i),
+                description: "Imported".to_string(),
+            })
+            .collect();
+        let (valid, errors) = validate_rows(&items, None);
+        assert!(errors.is_empty());
+
+        for repo in test_repositories().await {
+            repo.import_to_bucket_list(&valid).await.unwrap();
+
+            let exported: Vec<BucketListItem> = page_items(repo).try_collect().await.unwrap();
+            // A new database starts with an example item.
+            assert_eq!(exported.len(), items.len() + 1);
+            assert!(exported.windows(2).all(|pair| pair[0].id > pair[1].id));
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::bucket_list::repository::{BucketListRepository, bucket_list_repository};
+use crate::bucket_list::transfer::{TransferFormat, page_items, validate_rows};
+use crate::cli::CliError;
+use crate::common::config::Config;
+use crate::common::error::ExtraResultExt;
+use error_stack::{Report, ResultExt};
+use futures_util::{StreamExt, TryStreamExt};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+async fn open_bucket_list_repository() -> Result<Box<dyn BucketListRepository>, Report<CliError>> {
+    let config = Config::fetch()
+        .await
+        .change_context(CliError::ConfigError)?;
+    bucket_list_repository(&config)
+        .await
+        .change_context(CliError::DatabaseError)
+}
+
+pub async fn export(
+    output: Option<PathBuf>,
+    format: TransferFormat,
+) -> Result<(), Report<CliError>> {
+    let repo = open_bucket_list_repository().await?;
+
+    let writer: Box<dyn Write> = match output.as_ref() {
+        Some(path) => Box::new(
+            File::create(path)
+                .change_context(CliError::IoError)
+                .attach_critical_lazy(|| format!("Unable to create '{}'", path.display()))?,
+        ),
+        None => Box::new(std::io::stdout()),
+    };
+    let mut writer = BufWriter::new(writer);
+    let mut count = 0;
+    let items = page_items(repo).inspect_ok(|_| count += 1);
+    let mut chunks = Box::pin(format.encode(items));
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk.change_context(CliError::IoError)?;
+        writer.write_all(&chunk).change_context(CliError::IoError)?;
+    }
+    drop(chunks);
+    writer.flush().change_context(CliError::IoError)?;
+
+    if let Some(path) = output {
+        eprintln!("Exported {} items to '{}'", count, path.display());
+    }
+    Ok(())
+}
+
+pub async fn import(input: PathBuf, format: TransferFormat) -> Result<(), Report<CliError>> {
+    let data = std::fs::read(&input)
+        .change_context(CliError::IoError)
+        .attach_critical_lazy(|| format!("Unable to read '{}'", input.display()))?;
+    let items = format
+        .decode(&data)
+        .change_context(CliError::InvalidInput)?;
+
+    let (valid, errors) = validate_rows(&items, None);
+    if !errors.is_empty() {
+        let mut report = Report::new(CliError::InvalidInput);
+        for error in errors {
+            for message in error.errors.name.iter() {
+                report = report.attach(format!("row {}: name: {}", error.row, message));
+            }
+            for message in error.errors.description.iter() {
+                report = report.attach(format!("row {}: description: {}", error.row, message));
+            }
+        }
+        return Err(report);
+    }
+
+    let imported = open_bucket_list_repository()
+        .await?
+        .import_to_bucket_list(&valid)
+        .await
+        .change_context(CliError::DatabaseError)?;
+    println!("Imported {} items", imported);
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use crate::bucket_list::transfer::TransferFormat;
+use crate::common::backup::{backup_database, create_snapshot, restore_snapshot};
+use crate::common::config::Config;
+use crate::common::config::database::DatabaseBackend;
+use crate::common::db::{SCHEMA_VERSION, SqliteClient};
+use crate::common::locale::check;
+use clap::{Parser, Subcommand};
+use error_stack::{Report, ResultExt};
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+pub mod bucket_list;
+pub mod user;
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("Config error")]
+    ConfigError,
+    #[error("Database error")]
+    DatabaseError,
+    #[error("IO error")]
+    IoError,
+    #[error("Invalid input")]
+    InvalidInput,
+}
+
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run the web server, the default when no command is given.
+    Serve,
+    /// Create or upgrade the database schema, then exit.
+    Migrate,
+    /// Add a user, the password is read from stdin.
+    CreateUser { username: String },
+    /// Replace a user's password, read from stdin, and log them out everywhere.
+    ResetPassword { username: String },
+    /// List users with their number of active login tokens.
+    ListUsers,
+    /// Delete expired login tokens.
+    PurgeTokens,
+    /// Write a snapshot of the live database, safe while the server is running.
+    Backup {
+        /// Defaults to `backup.directory`.
+        #[arg(short, long)]
+        directory: Option<PathBuf>,
+    },
+    /// Replace the database with a snapshot, stop the server first.
+    Restore { snapshot: PathBuf },
+    /// Print every config value with its source, then validate.
+    CheckConfig,
+    /// Compare every locale with en-GB, fails on any problem so CI can run it.
+    CheckTranslations,
+    /// Write the bucket list as JSON or CSV.
+    Export {
+        /// Defaults to stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[arg(short, long, value_enum, default_value_t)]
+        format: TransferFormat,
+    },
+    /// Add bucket list items from a JSON or CSV file, nothing is added if any item is invalid.
+    Import {
+        input: PathBuf,
+        #[arg(short, long, value_enum, default_value_t)]
+        format: TransferFormat,
+    },
+}
+
+/// Runs every command except `serve`, which belongs to `main`.
+pub async fn run(command: Command) -> Result<(), Report<CliError>> {
+    let result = match command {
+        Command::Serve => Ok(()),
+        Command::CheckConfig => return check_config(),
+        Command::CheckTranslations => return check_translations(),
+        Command::Migrate => migrate().await,
+        Command::CreateUser { username } => user::create_user(username).await,
+        Command::ResetPassword { username } => user::reset_password(username).await,
+        Command::ListUsers => user::list_users().await,
+        Command::PurgeTokens => user::purge_tokens().await,
+        Command::Backup { directory } => backup(directory).await,
+        Command::Restore { snapshot } => return restore(snapshot).await,
+        Command::Export { output, format } => bucket_list::export(output, format).await,
+        Command::Import { input, format } => bucket_list::import(input, format).await,
+    };
+    SqliteClient::close_cached().change_context(CliError::DatabaseError)?;
+    result
+}
+
+/// The same config and SQLite database the server would use.
+pub async fn open_database() -> Result<SqliteClient, Report<CliError>> {
+    let config = Config::fetch()
+        .await
+        .change_context(CliError::ConfigError)?;
+    SqliteClient::fetch(&config)
+        .await
+        .change_context(CliError::DatabaseError)
+}
+
+/// Reads one line from stdin, prompting only when attached to a terminal.
+pub fn read_password(prompt: &str) -> Result<String, Report<CliError>> {
+    let stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        eprint!("{}: ", prompt);
+        std::io::stderr()
+            .flush()
+            .change_context(CliError::IoError)?;
+    }
+    let mut password = String::new();
+    stdin
+        .lock()
+        .read_line(&mut password)
+        .change_context(CliError::IoError)?;
+    Ok(password.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn check_config() -> Result<(), Report<CliError>> {
+    let sources = Config::sources().change_context(CliError::ConfigError)?;
+    let key_width = sources
+        .iter()
+        .map(|s| s.key.len())
+        .max()
+        .unwrap_or_default();
+    for source in sources {
+        println!(
+            "{:key_width$} = {}  # {}",
+            source.key, source.value, source.source
+        );
+    }
+
+    Config::parse().change_context(CliError::ConfigError)?;
+    println!("Config is valid");
+    Ok(())
+}
+
+fn check_translations() -> Result<(), Report<CliError>> {
+    let problems = check::check_translations();
+    if problems.is_empty() {
+        println!("Translations are complete");
+        return Ok(());
+    }
+    for problem in problems.iter() {
+        println!("{}", problem);
+    }
+    Err(Report::new(CliError::InvalidInput)
+        .attach(format!("{} translation problem(s)", problems.len())))
+}
+
+async fn migrate() -> Result<(), Report<CliError>> {
+    let config = Config::fetch()
+        .await
+        .change_context(CliError::ConfigError)?;
+    match config.database.backend {
+        DatabaseBackend::Sqlite => {}
+        #[cfg(feature = "postgres")]
+        DatabaseBackend::Postgres => {
+            crate::common::db::postgres::PostgresClient::fetch(&config)
+                .await
+                .change_context(CliError::DatabaseError)?;
+            println!("Postgres schema is ready");
+            return Ok(());
+        }
+        #[cfg(not(feature = "postgres"))]
+        DatabaseBackend::Postgres => {
+            return Err(Report::new(CliError::ConfigError)
+                .attach("Built without the postgres feature".to_string()));
+        }
+    }
+
+    let version = open_database()
+        .await?
+        .schema_version()
+        .change_context(CliError::DatabaseError)?;
+    if version != SCHEMA_VERSION {
+        return Err(Report::new(CliError::DatabaseError).attach(format!(
+            "Schema version is {version}, expected {SCHEMA_VERSION}"
+        )));
+    }
+    println!("Database schema is at version {}", version);
+    Ok(())
+}
+
+async fn backup(directory: Option<PathBuf>) -> Result<(), Report<CliError>> {
+    let config = Config::fetch()
+        .await
+        .change_context(CliError::ConfigError)?;
+    if config.database.backend != DatabaseBackend::Sqlite {
+        return Err(Report::new(CliError::DatabaseError)
+            .attach("Backups need the sqlite backend".to_string()));
+    }
+    let path = match directory {
+        None => backup_database(&config)
+            .await
+            .change_context(CliError::DatabaseError)?,
+        Some(directory) => {
+            create_snapshot(&open_database().await?, &directory, config.backup.retention)
+                .change_context(CliError::DatabaseError)?
+        }
+    };
+    println!("Backup written to '{}'", path.display());
+    Ok(())
+}
+
+/// Never opens the database itself, so nothing holds the file being replaced.
+async fn restore(snapshot: PathBuf) -> Result<(), Report<CliError>> {
+    let config = Config::fetch()
+        .await
+        .change_context(CliError::ConfigError)?;
+    restore_snapshot(&snapshot, Path::new(&config.sqlite.path))
+        .change_context(CliError::DatabaseError)?;
+    println!(
+        "Restored '{}' to '{}'",
+        snapshot.display(),
+        config.sqlite.path
+    );
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use crate::cli::{CliError, read_password};
+use crate::common::config::Config;
+use crate::common::locale::LocaleExtForResult;
+use crate::user::form::UserRegisterForm;
+use crate::user::model::UserLoginFormValidationErrorMessage;
+use crate::user::repository::UserRepository;
+use crate::user::repository::user_repository;
+use crate::user::service::{UserAdminService, UserRegisterService};
+use error_stack::{Report, ResultExt};
+
+fn invalid(fields: &[(&str, &[String])]) -> Report<CliError> {
+    let mut report = Report::new(CliError::InvalidInput);
+    for (field, messages) in fields {
+        for message in messages.iter() {
+            report = report.attach(format!("{}: {}", field, message));
+        }
+    }
+    report
+}
+
+async fn open_user_repository() -> Result<Box<dyn UserRepository>, Report<CliError>> {
+    let config = Config::fetch()
+        .await
+        .change_context(CliError::ConfigError)?;
+    user_repository(&config)
+        .await
+        .change_context(CliError::DatabaseError)
+}
+
+async fn user_admin_service() -> Result<UserAdminService, Report<CliError>> {
+    Ok(UserAdminService::new(open_user_repository().await?))
+}
+
+pub async fn create_user(username: String) -> Result<(), Report<CliError>> {
+    let password = read_password("Password")?;
+    let service = UserRegisterService::new(open_user_repository().await?);
+
+    let form = UserRegisterForm {
+        username,
+        password: password.clone(),
+        password_confirm: password,
+        ..UserRegisterForm::default()
+    };
+    match form.as_validated(&service).await.0 {
+        Ok(validated) => {
+            let username = validated.username.as_str().to_string();
+            if !service
+                .register_user(username.clone(), validated.password.as_str().to_string())
+                .await
+            {
+                return Err(Report::new(CliError::DatabaseError)
+                    .attach(format!("Failed to create user '{}'", username)));
+            }
+            println!("Created user '{}'", username);
+            Ok(())
+        }
+        Err(err) => {
+            let messages: UserLoginFormValidationErrorMessage = err.into();
+            Err(invalid(&[
+                ("username", &messages.username),
+                ("password", &messages.password),
+            ]))
+        }
+    }
+}
+
+pub async fn reset_password(username: String) -> Result<(), Report<CliError>> {
+    let password = read_password("New password")?;
+    let password = UserRegisterForm::validate_password(password.as_str());
+    let password = match password.as_ref() {
+        Ok(password) => password,
+        Err(_) => return Err(invalid(&[("password", &password.as_original_message())])),
+    };
+
+    user_admin_service()
+        .await?
+        .reset_password(username.clone(), password.as_str().to_string())
+        .await
+        .change_context(CliError::InvalidInput)?;
+    println!("Password reset for '{}', all sessions logged out", username);
+    Ok(())
+}
+
+pub async fn list_users() -> Result<(), Report<CliError>> {
+    let users = user_admin_service()
+        .await?
+        .list_users()
+        .await
+        .change_context(CliError::DatabaseError)?;
+    let name_width = users
+        .iter()
+        .map(|u| u.username.len())
+        .max()
+        .unwrap_or_default()
+        .max("USERNAME".len());
+    println!("{:>6}  {:name_width$}  ACTIVE TOKENS", "ID", "USERNAME");
+    for user in users.iter() {
+        println!(
+            "{:>6}  {:name_width$}  {}",
+            user.id, user.username, user.active_tokens
+        );
+    }
+    Ok(())
+}
+
+pub async fn purge_tokens() -> Result<(), Report<CliError>> {
+    let purged = user_admin_service()
+        .await?
+        .purge_expired_tokens()
+        .await
+        .change_context(CliError::DatabaseError)?;
+    println!("Purged {} expired login tokens", purged);
+    Ok(())
+}
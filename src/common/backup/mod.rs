@@ -0,0 +1,333 @@
+use crate::common::config::Config;
+use crate::common::config::database::DatabaseBackend;
+use crate::common::db::{SCHEMA_VERSION, SqliteClient};
+use crate::common::error::{
+    ErrorReportResponse, ExtraResultExt, FromIntoStackError, JsonErrorOutput,
+};
+use crate::common::security::has_bearer_token;
+use crate::common::shutdown::Shutdown;
+use chrono::Utc;
+use error_stack::{Report, ResultExt};
+use poem::error::ResponseError;
+use poem::http::StatusCode;
+use poem::web::Json;
+use poem::{IntoResponse, Request, Response, handler};
+use rusqlite::{Connection, OpenFlags};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::task::spawn_blocking;
+
+pub const BACKUP_PATH: &str = "/admin/backup";
+
+const SNAPSHOT_PREFIX: &str = "little_poem-";
+const SNAPSHOT_SUFFIX: &str = ".db";
+/// Created by the first migration, so any snapshot this build can migrate has them.
+const SNAPSHOT_TABLES: [&str; 3] = ["users", "user_login_tokens", "bucket_list"];
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("Backup directory not configured")]
+    NotConfigured,
+    #[error("Database error")]
+    Database,
+    #[error("IO error")]
+    Io,
+    #[error("Snapshot is not compatible")]
+    Incompatible,
+    #[error("Backups need the sqlite backend")]
+    Unsupported,
+    #[error("An earlier restore was not cleaned up")]
+    PreRestoreExists,
+}
+
+impl FromIntoStackError for BackupError {}
+
+impl ResponseError for BackupError {
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::NotConfigured | Self::Unsupported => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+fn is_snapshot(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.starts_with(SNAPSHOT_PREFIX) && name.ends_with(SNAPSHOT_SUFFIX))
+        .unwrap_or_default()
+}
+
+/// Deletes the oldest snapshots beyond `retention`, names sort by their timestamp.
+fn prune_snapshots(directory: &Path, retention: usize) -> Result<(), Report<BackupError>> {
+    let mut snapshots: Vec<PathBuf> = std::fs::read_dir(directory)
+        .change_context(BackupError::Io)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| is_snapshot(path))
+        .collect();
+    snapshots.sort();
+
+    let excess = snapshots.len().saturating_sub(retention);
+    for snapshot in snapshots.into_iter().take(excess) {
+        std::fs::remove_file(&snapshot)
+            .change_context(BackupError::Io)
+            .attach_critical_lazy(|| format!("Unable to delete '{}'", snapshot.display()))?;
+    }
+    Ok(())
+}
+
+/// Writes a timestamped snapshot into `directory`, then applies the retention.
+pub fn create_snapshot(
+    sqlite_client: &SqliteClient,
+    directory: &Path,
+    retention: usize,
+) -> Result<PathBuf, Report<BackupError>> {
+    let name = format!(
+        "{}{}{}",
+        SNAPSHOT_PREFIX,
+        Utc::now().format("%Y%m%dT%H%M%S%.3fZ"),
+        SNAPSHOT_SUFFIX
+    );
+    let path = directory.join(&name);
+    // Written under another name first, so a half-written file never counts as a snapshot.
+    let partial = directory.join(format!("{}.partial", name));
+    sqlite_client
+        .backup_to(&partial)
+        .change_context(BackupError::Database)?;
+    std::fs::rename(&partial, &path).change_context(BackupError::Io)?;
+
+    prune_snapshots(directory, retention)?;
+    Ok(path)
+}
+
+/// Snapshot of the configured database into `backup.directory`.
+pub async fn backup_database(config: &Config) -> Result<PathBuf, Report<BackupError>> {
+    if config.database.backend != DatabaseBackend::Sqlite {
+        return Err(Report::new(BackupError::Unsupported));
+    }
+    let directory = config
+        .backup
+        .directory
+        .clone()
+        .ok_or(BackupError::NotConfigured)?;
+    let retention = config.backup.retention;
+    let sqlite_client = SqliteClient::fetch(config)
+        .await
+        .change_context(BackupError::Database)?;
+
+    spawn_blocking(move || create_snapshot(&sqlite_client, Path::new(&directory), retention))
+        .await
+        .change_context(BackupError::Io)?
+}
+
+/// Checks `snapshot` is intact and on this build's schema, then swaps it in for `target`.
+/// Only safe with the server stopped, the replaced file is kept as `<target>.pre-restore`
+/// and a restore is refused while an earlier one is still there.
+pub fn restore_snapshot(snapshot: &Path, target: &Path) -> Result<(), Report<BackupError>> {
+    let target_name = target.display().to_string();
+    let pre_restore = format!("{}.pre-restore", target_name);
+    if Path::new(&pre_restore).exists() {
+        return Err(
+            BackupError::PreRestoreExists.into_stack_error_critical(format!(
+                "Move '{}' away before restoring again",
+                pre_restore
+            )),
+        );
+    }
+
+    let conn = Connection::open_with_flags(snapshot, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .change_context(BackupError::Database)
+        .attach_critical_lazy(|| format!("Unable to open '{}'", snapshot.display()))?;
+    let integrity: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .change_context(BackupError::Incompatible)
+        .attach_critical("Snapshot is not a SQLite database".to_string())?;
+    if integrity != "ok" {
+        return Err(BackupError::Incompatible
+            .into_stack_error_critical(format!("Integrity check failed: {}", integrity)));
+    }
+    let version: i64 = conn
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .change_context(BackupError::Database)?;
+    // Older snapshots are migrated the next time the database is opened.
+    if version > SCHEMA_VERSION {
+        return Err(BackupError::Incompatible.into_stack_error_critical(format!(
+            "Snapshot schema version is {version}, newer than {SCHEMA_VERSION}"
+        )));
+    }
+    let mut missing = Vec::new();
+    for table in SNAPSHOT_TABLES {
+        let count: i64 = conn
+            .query_row(
+                "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                [table],
+                |row| row.get(0),
+            )
+            .change_context(BackupError::Database)?;
+        if count == 0 {
+            missing.push(table);
+        }
+    }
+    if !missing.is_empty() {
+        return Err(BackupError::Incompatible.into_stack_error_critical(format!(
+            "Snapshot is missing the {} table(s)",
+            missing.join(", ")
+        )));
+    }
+    drop(conn);
+
+    let staging = PathBuf::from(format!("{}.restore", target_name));
+    std::fs::copy(snapshot, &staging).change_context(BackupError::Io)?;
+    if target.exists() {
+        std::fs::rename(target, &pre_restore).change_context(BackupError::Io)?;
+    }
+    // A hot journal or WAL of the old file would be rolled back or replayed onto the
+    // restored one, so they move with the old file and stay usable with it.
+    for suffix in ["-journal", "-wal", "-shm"] {
+        let side_file = PathBuf::from(format!("{}{}", target_name, suffix));
+        if side_file.exists() {
+            std::fs::rename(&side_file, format!("{}{}", pre_restore, suffix))
+                .change_context(BackupError::Io)
+                .attach_critical_lazy(|| {
+                    format!("Unable to move '{}' aside", side_file.display())
+                })?;
+        }
+    }
+    std::fs::rename(&staging, target).change_context(BackupError::Io)?;
+    Ok(())
+}
+
+/// Backs up every `backup.interval_secs` while `backup.directory` is set, both are
+/// re-read each round so a config reload applies.
+pub fn schedule_backups() {
+    tokio::spawn(async {
+        loop {
+            let Ok(config) = Config::fetch().await else {
+                return;
+            };
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(config.backup.interval_secs)) => {}
+                _ = Shutdown::wait() => return,
+            }
+
+            let Ok(config) = Config::fetch().await else {
+                return;
+            };
+            if config.backup.directory.is_none() {
+                continue;
+            }
+            match backup_database(&config).await {
+                Ok(path) => println!("Backup written to '{}'", path.display()),
+                Err(err) => eprintln!("Backup failed: {:?}", err),
+            }
+        }
+    });
+}
+
+#[handler]
+pub async fn backup_now(req: &Request) -> Response {
+    let config = match Config::fetch().await {
+        Ok(config) => config,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    let Some(token) = config.backup.bearer_token.as_deref() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if !has_bearer_token(req, token) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    match backup_database(&config).await {
+        Ok(path) => Json(json!({"status": "ok", "path": path.display().to_string()}))
+            .with_status(StatusCode::CREATED)
+            .into_response(),
+        Err(err) => ErrorReportResponse::<BackupError, JsonErrorOutput>::new(err).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    /// A temporary directory with a snapshot holding the app tables plus `extra_sql`.
+    fn directory_with_snapshot(extra_sql: &str) -> (PathBuf, PathBuf, PathBuf) {
+        let directory = std::env::temp_dir().join(format!("little_poem-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let snapshot = directory.join("snapshot.db");
+        let target = directory.join("sqlite.db");
+        Connection::open(&snapshot)
+            .unwrap()
+            .execute_batch(&format!(
+                "{}\n{}",
+                include_str!("../db/_sql/init.sql"),
+                extra_sql
+            ))
+            .unwrap();
+        Connection::open(&target)
+            .unwrap()
+            .execute_batch("CREATE TABLE replaced (id INTEGER)")
+            .unwrap();
+        (directory, snapshot, target)
+    }
+
+    fn has_table(path: &Path, table: &str) -> bool {
+        Connection::open(path)
+            .unwrap()
+            .query_row(
+                "SELECT count(*) FROM sqlite_master WHERE name = ?1",
+                [table],
+                |row| row.get::<_, i64>(0),
+            )
+            .unwrap()
+            == 1
+    }
+
+    #[test]
+    fn restore_moves_the_old_journal_aside() {
+        let (directory, snapshot, target) =
+            directory_with_snapshot("CREATE TABLE restored (id INTEGER);");
+        let journal = directory.join("sqlite.db-journal");
+        std::fs::write(&journal, b"hot journal").unwrap();
+
+        restore_snapshot(&snapshot, &target).unwrap();
+
+        assert!(!journal.exists());
+        assert_eq!(
+            std::fs::read(directory.join("sqlite.db.pre-restore-journal")).unwrap(),
+            b"hot journal"
+        );
+        assert!(has_table(&target, "restored"));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn restore_refuses_a_database_without_the_app_tables() {
+        let (directory, snapshot, target) = directory_with_snapshot("DROP TABLE bucket_list;");
+
+        let err = restore_snapshot(&snapshot, &target).err().unwrap();
+
+        assert!(matches!(err.current_context(), BackupError::Incompatible));
+        assert!(has_table(&target, "replaced"));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn restore_keeps_an_earlier_pre_restore() {
+        let (directory, snapshot, target) = directory_with_snapshot("");
+        let pre_restore = directory.join("sqlite.db.pre-restore");
+        std::fs::write(&pre_restore, b"earlier").unwrap();
+
+        let err = restore_snapshot(&snapshot, &target).err().unwrap();
+
+        assert!(matches!(
+            err.current_context(),
+            BackupError::PreRestoreExists
+        ));
+        assert_eq!(std::fs::read(&pre_restore).unwrap(), b"earlier");
+        assert!(has_table(&target, "replaced"));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BackupConfig {
+    /// Where snapshots are written, scheduled backups are off when unset.
+    pub directory: Option<String>,
+    pub interval_secs: u64,
+    /// Number of snapshots kept, older ones are deleted after each backup.
+    pub retention: usize,
+    /// Required as `Authorization: Bearer <token>` on the backup endpoint, which is off when unset.
+    pub bearer_token: Option<String>,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            interval_secs: 24 * 60 * 60,
+            retention: 7,
+            bearer_token: None,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use crate::common::config::{Config, ConfigError};
+use error_stack::{Report, ResultExt};
+use figment::value::{Dict, Value};
+use figment::{Figment, Source};
+
+pub struct ConfigValueSource {
+    pub key: String,
+    pub value: String,
+    pub source: String,
+}
+
+fn describe_source(figment: &Figment, value: &Value) -> String {
+    match figment.get_metadata(value.tag()) {
+        None => "unknown".to_string(),
+        Some(metadata) => match metadata.source.as_ref() {
+            Some(Source::Code(_)) => "default".to_string(),
+            Some(Source::File(_)) => metadata
+                .source
+                .as_ref()
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+            _ => metadata.name.to_string(),
+        },
+    }
+}
+
+fn walk(figment: &Figment, key: String, value: &Value, out: &mut Vec<ConfigValueSource>) {
+    match value {
+        Value::Dict(_, dict) => {
+            for (name, value) in dict.iter() {
+                let key = if key.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}.{}", key, name)
+                };
+                walk(figment, key, value, out);
+            }
+        }
+        Value::Array(_, values) if !values.is_empty() => {
+            for (i, value) in values.iter().enumerate() {
+                walk(figment, format!("{}[{}]", key, i), value, out);
+            }
+        }
+        _ => out.push(ConfigValueSource {
+            key,
+            value: serde_json::to_string(value).unwrap_or_default(),
+            source: describe_source(figment, value),
+        }),
+    }
+}
+
+impl Config {
+    /// Every leaf of the merged config with the provider that won for it.
+    pub fn sources() -> Result<Vec<ConfigValueSource>, Report<ConfigError>> {
+        let figment = Self::build_figment();
+        let keys = figment
+            .extract::<Dict>()
+            .change_context(ConfigError::ParseError)?;
+
+        let mut out = Vec::new();
+        for key in keys.keys() {
+            let value = figment
+                .find_value(key)
+                .change_context(ConfigError::ParseError)?;
+            walk(&figment, key.clone(), &value, &mut out);
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DatabaseBackend {
+    #[default]
+    Sqlite,
+    /// Needs the `postgres` cargo feature.
+    Postgres,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct DatabaseConfig {
+    pub backend: DatabaseBackend,
+    /// Connection string for `backend = "postgres"`, e.g. `host=localhost user=poem dbname=poem`.
+    pub postgres_url: Option<String>,
+}
@@ -0,0 +1,126 @@
+use figment::value::{Dict, Map, Value};
+use figment::{Error, Metadata, Profile, Provider};
+
+/// Reads `<PREFIX><KEY>_FILE=/path` variables and uses the file contents as the value of
+/// `<KEY>`, so secrets can come from mounted files instead of the environment itself.
+/// The contents are parsed like `Env` values, so `<PREFIX>POEM__PORT_FILE` yields a number.
+pub struct EnvFile {
+    prefix: String,
+    split: String,
+}
+
+impl EnvFile {
+    pub fn prefixed(prefix: &str, split: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            split: split.to_string(),
+        }
+    }
+
+    fn keys_and_paths(
+        &self,
+        vars: impl Iterator<Item = (String, String)>,
+    ) -> Vec<(String, String)> {
+        vars.filter_map(|(key, path)| {
+            let key = key.strip_prefix(&self.prefix)?.strip_suffix("_FILE")?;
+            Some((key.to_ascii_lowercase().replace(&self.split, "."), path))
+        })
+        .collect()
+    }
+}
+
+fn secret_value(contents: &str) -> Value {
+    contents
+        .trim_end_matches(['\r', '\n'])
+        .parse()
+        .expect("infallible")
+}
+
+fn insert_nested(dict: &mut Dict, key: &str, value: Value) {
+    match key.split_once('.') {
+        None => {
+            dict.insert(key.to_string(), value);
+        }
+        Some((head, rest)) => {
+            let child = dict
+                .entry(head.to_string())
+                .or_insert_with(|| Value::from(Dict::new()));
+            if !matches!(child, Value::Dict(..)) {
+                *child = Value::from(Dict::new());
+            }
+            if let Value::Dict(_, child) = child {
+                insert_nested(child, rest, value);
+            }
+        }
+    }
+}
+
+impl Provider for EnvFile {
+    fn metadata(&self) -> Metadata {
+        Metadata::named(format!("`{}*_FILE` secret file(s)", self.prefix))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut dict = Dict::new();
+        for (key, path) in self.keys_and_paths(std::env::vars()) {
+            let contents = std::fs::read_to_string(&path).map_err(|err| {
+                Error::from(format!(
+                    "unable to read secret file '{}' for '{}': {}",
+                    path, key, err
+                ))
+            })?;
+            insert_nested(&mut dict, &key, secret_value(&contents));
+        }
+        Ok(Profile::Global.collect(dict))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Poem {
+        port: u16,
+        secure: bool,
+        name: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Nested {
+        poem: Poem,
+    }
+
+    #[test]
+    fn secret_files_are_parsed_like_env_values() {
+        let dir = std::env::temp_dir().join(format!("little_poem_env_file_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let write = |name: &str, contents: &str| {
+            let path = dir.join(name);
+            std::fs::write(&path, contents).unwrap();
+            path.display().to_string()
+        };
+        let vars = vec![
+            ("APP_POEM__PORT_FILE".to_string(), write("port", "9000\n")),
+            ("APP_POEM__SECURE_FILE".to_string(), write("secure", "true")),
+            (
+                "APP_POEM__NAME_FILE".to_string(),
+                write("name", "little poem\n"),
+            ),
+            ("OTHER_PORT_FILE".to_string(), write("other", "1")),
+        ];
+
+        let mut dict = Dict::new();
+        for (key, path) in EnvFile::prefixed("APP_", "__").keys_and_paths(vars.into_iter()) {
+            let contents = std::fs::read_to_string(path).unwrap();
+            insert_nested(&mut dict, &key, secret_value(&contents));
+        }
+        let nested: Nested = Value::from(dict).deserialize().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(nested.poem.port, 9000);
+        assert!(nested.poem.secure);
+        assert_eq!(nested.poem.name, "little poem");
+    }
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MetricsConfig {
+    /// Serve `/metrics` on its own listener, e.g. `127.0.0.1:9100`.
+    pub address: Option<String>,
+    /// Require `Authorization: Bearer <token>` on scrape requests.
+    /// `/metrics` is only nested into the main listener when this is set.
+    pub bearer_token: Option<String>,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            address: Some("127.0.0.1:9100".to_string()),
+            bearer_token: None,
+        }
+    }
+}
@@ -1,27 +1,55 @@
+use crate::common::config::backup::BackupConfig;
+use crate::common::config::database::DatabaseConfig;
+use crate::common::config::health::HealthConfig;
+use crate::common::config::metrics::MetricsConfig;
 use crate::common::config::poem::PoemConfig;
+use crate::common::config::proxy::ProxyConfig;
+use crate::common::config::security::SecurityHeadersConfig;
+use arc_swap::ArcSwap;
+use env_file::EnvFile;
 use error_stack::{Report, ResultExt};
-use figment::providers::{Format, Serialized, Toml};
+use figment::providers::{Env, Format, Serialized, Toml};
 use figment::{Figment, Profile};
 use serde::{Deserialize, Serialize};
 use sqlite::SqliteConfig;
 use std::env::var;
-use std::sync::{Arc, Weak};
+use std::sync::Arc;
 use thiserror::Error;
 use tokio::sync::OnceCell;
+#[cfg(test)]
+use tokio::sync::{Mutex, MutexGuard};
 
+pub mod backup;
+pub mod check;
+pub mod database;
+pub mod env_file;
+pub mod health;
+pub mod metrics;
 pub mod poem;
+pub mod proxy;
+pub mod reload;
+pub mod security;
 pub mod sqlite;
+pub mod validate;
 
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error("Config did not parse")]
     ParseError,
+    #[error("Config is invalid")]
+    ValidationError,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Config {
     pub poem: Arc<PoemConfig>,
     pub sqlite: Arc<SqliteConfig>,
+    pub database: Arc<DatabaseConfig>,
+    pub metrics: Arc<MetricsConfig>,
+    pub health: Arc<HealthConfig>,
+    pub proxy: Arc<ProxyConfig>,
+    pub security_headers: Arc<SecurityHeadersConfig>,
+    pub backup: Arc<BackupConfig>,
 }
 
 impl Default for Config {
@@ -29,11 +57,24 @@ impl Default for Config {
         Self {
             poem: Arc::new(PoemConfig::default()),
             sqlite: Arc::new(SqliteConfig::default()),
+            database: Arc::new(DatabaseConfig::default()),
+            metrics: Arc::new(MetricsConfig::default()),
+            health: Arc::new(HealthConfig::default()),
+            proxy: Arc::new(ProxyConfig::default()),
+            security_headers: Arc::new(SecurityHeadersConfig::default()),
+            backup: Arc::new(BackupConfig::default()),
         }
     }
 }
 
-static CONFIG_CACHE: OnceCell<Arc<Config>> = OnceCell::const_new();
+/// `LITTLE_POEM_POEM__SHUTDOWN_TIMEOUT_SECS=10` overrides `poem.shutdown_timeout_secs`.
+/// Lists cannot be indexed, `LITTLE_POEM_POEM__PORT` covers the usual listener override.
+const ENV_PREFIX: &str = "LITTLE_POEM_";
+const ENV_SPLIT: &str = "__";
+
+static CONFIG_CACHE: OnceCell<ArcSwap<Config>> = OnceCell::const_new();
+#[cfg(test)]
+static INSTALL_LOCK: Mutex<()> = Mutex::const_new(());
 
 impl Config {
     fn build_figment() -> Figment {
@@ -47,23 +88,49 @@ impl Config {
                 )
                 .nested(),
             )
+            .merge(
+                Env::prefixed(ENV_PREFIX)
+                    .ignore(&["CONFIG_PATH", "PROFILE"])
+                    .filter(|key| !key.as_str().to_ascii_uppercase().ends_with("_FILE"))
+                    .split(ENV_SPLIT)
+                    .global(),
+            )
+            .merge(EnvFile::prefixed(ENV_PREFIX, ENV_SPLIT))
             .select(Profile::from_env_or("LITTLE_POEM_PROFILE", "default"))
     }
 
-    fn parse() -> Result<Self, Report<ConfigError>> {
-        Self::build_figment()
+    /// Parses and validates the config without touching the cache.
+    pub fn parse() -> Result<Self, Report<ConfigError>> {
+        let mut config = Self::build_figment()
             .extract::<Self>()
-            .change_context(ConfigError::ParseError)
+            .change_context(ConfigError::ParseError)?;
+        config.poem = Arc::new(config.poem.with_port_shorthand());
+        config.validate()?;
+        Ok(config)
     }
 
-    pub async fn fetch() -> Result<Weak<Config>, Report<ConfigError>> {
-        let config: Result<&Arc<Config>, Report<ConfigError>> = CONFIG_CACHE
+    /// Snapshot of the current config, hold it for one request rather than across reloads.
+    pub async fn fetch() -> Result<Arc<Config>, Report<ConfigError>> {
+        let config: Result<&ArcSwap<Config>, Report<ConfigError>> = CONFIG_CACHE
             .get_or_try_init(|| async {
                 let config = Self::parse()?;
-                Ok(Arc::new(config))
+                Ok(ArcSwap::from_pointee(config))
             })
             .await;
 
-        Ok(Arc::downgrade(config?))
+        Ok(config?.load_full())
+    }
+
+    /// Replaces the cached config, so tests can run the app without config files.
+    /// Tests share the cache, so hold the guard for as long as the test relies on it.
+    #[cfg(test)]
+    pub async fn install(config: Config) -> (Arc<Config>, MutexGuard<'static, ()>) {
+        let guard = INSTALL_LOCK.lock().await;
+        let config = Arc::new(config);
+        CONFIG_CACHE
+            .get_or_init(|| async { ArcSwap::new(Arc::clone(&config)) })
+            .await
+            .store(Arc::clone(&config));
+        (config, guard)
     }
 }
@@ -1,22 +1,107 @@
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ListenerConfig {
+    Tcp {
+        address: String,
+        port: u16,
+    },
+    Unix {
+        path: String,
+        /// File permissions for the socket, e.g. `mode = 0o660`.
+        mode: Option<u32>,
+    },
+    Tls {
+        address: String,
+        port: u16,
+        cert_path: String,
+        key_path: String,
+        /// Plain HTTP port on the same address that redirects every request to HTTPS.
+        redirect_port: Option<u16>,
+    },
+}
+
+impl ListenerConfig {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Tcp { address, port } => format!("http://{}:{}", address, port),
+            Self::Unix { path, .. } => format!("unix:{}", path),
+            Self::Tls { address, port, .. } => format!("https://{}:{}", address, port),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct PoemConfig {
-    pub address: String,
-    pub port: u16,
+    pub listeners: Vec<ListenerConfig>,
+    /// Shorthand for the port of the first TCP listener, so `LITTLE_POEM_POEM__PORT`
+    /// works without repeating the whole `listeners` list.
+    pub port: Option<u16>,
+    pub shutdown_timeout_secs: u64,
 }
 
 impl Default for PoemConfig {
     fn default() -> Self {
         Self {
-            address: "127.0.0.1".to_string(),
-            port: 8000,
+            listeners: vec![ListenerConfig::Tcp {
+                address: "127.0.0.1".to_string(),
+                port: 8000,
+            }],
+            port: None,
+            shutdown_timeout_secs: 30,
         }
     }
 }
 
 impl PoemConfig {
-    pub fn parse_address(&self) -> String {
-        format!("{}:{}", self.address, self.port)
+    /// The config with `port` moved onto the first TCP listener.
+    pub fn with_port_shorthand(&self) -> Self {
+        let mut config = self.clone();
+        if let Some(new_port) = config.port
+            && let Some(ListenerConfig::Tcp { port, .. }) = config
+                .listeners
+                .iter_mut()
+                .find(|listener| matches!(listener, ListenerConfig::Tcp { .. }))
+        {
+            *port = new_port;
+        }
+        config
+    }
+
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(self.shutdown_timeout_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn port_shorthand_moves_the_first_tcp_listener() {
+        let config = PoemConfig {
+            listeners: vec![
+                ListenerConfig::Unix {
+                    path: "poem.sock".to_string(),
+                    mode: None,
+                },
+                ListenerConfig::Tcp {
+                    address: "0.0.0.0".to_string(),
+                    port: 8000,
+                },
+                ListenerConfig::Tcp {
+                    address: "::".to_string(),
+                    port: 8001,
+                },
+            ],
+            port: Some(9000),
+            ..PoemConfig::default()
+        }
+        .with_port_shorthand();
+
+        assert_eq!(config.listeners[1].describe(), "http://0.0.0.0:9000");
+        assert_eq!(config.listeners[2].describe(), "http://:::8001");
     }
 }
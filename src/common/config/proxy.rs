@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ProxyConfig {
+    /// CIDRs whose forwarding headers are honoured, e.g. `["127.0.0.1/32", "10.0.0.0/8"]`.
+    pub trusted: Vec<String>,
+    /// Peers connecting over a unix socket are local, so they are trusted by default.
+    pub trust_unix_socket: bool,
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        Self {
+            trusted: Vec::new(),
+            trust_unix_socket: true,
+        }
+    }
+}
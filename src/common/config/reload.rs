@@ -0,0 +1,158 @@
+use crate::common::config::metrics::MetricsConfig;
+use crate::common::config::{CONFIG_CACHE, Config, ConfigError};
+use crate::common::shutdown::{Shutdown, hangup_signal, wait_for_hangup};
+use error_stack::Report;
+use std::sync::Arc;
+
+impl Config {
+    /// Keeps the running value of every setting that is only read at startup,
+    /// returning the keys whose change was rejected.
+    fn keep_restart_only(&mut self, current: &Config) -> Vec<&'static str> {
+        let mut rejected = Vec::new();
+        if self.poem != current.poem {
+            rejected.push("poem");
+            self.poem = Arc::clone(&current.poem);
+        }
+        if self.sqlite != current.sqlite {
+            rejected.push("sqlite");
+            self.sqlite = Arc::clone(&current.sqlite);
+        }
+        if self.database != current.database {
+            rejected.push("database");
+            self.database = Arc::clone(&current.database);
+        }
+        if self.proxy != current.proxy {
+            rejected.push("proxy");
+            self.proxy = Arc::clone(&current.proxy);
+        }
+        if self.metrics.address != current.metrics.address {
+            rejected.push("metrics.address");
+            self.metrics = Arc::new(MetricsConfig {
+                address: current.metrics.address.clone(),
+                bearer_token: self.metrics.bearer_token.clone(),
+            });
+        }
+        rejected
+    }
+
+    /// Re-reads every source and swaps the result in, an invalid config keeps the current one.
+    /// Security headers, the metrics and backup bearer tokens, the backup schedule and the
+    /// health timeout apply from the next request; listeners, databases, proxy and
+    /// `metrics.address` need a restart.
+    pub async fn reload() -> Result<(), Report<ConfigError>> {
+        Self::replace(Self::parse()?).await
+    }
+
+    async fn replace(mut config: Config) -> Result<(), Report<ConfigError>> {
+        let Some(cache) = CONFIG_CACHE.get() else {
+            Self::fetch().await?;
+            return Ok(());
+        };
+
+        let rejected = config.keep_restart_only(&cache.load());
+        if !rejected.is_empty() {
+            eprintln!(
+                "Config reload ignored changes that need a restart: {}",
+                rejected.join(", ")
+            );
+        }
+        cache.store(Arc::new(config));
+        Ok(())
+    }
+}
+
+/// Reloads the config every time the process receives SIGHUP, until shutdown.
+pub fn listen_for_reload_signal() {
+    tokio::spawn(async {
+        let mut hangup = hangup_signal();
+        loop {
+            tokio::select! {
+                received = wait_for_hangup(&mut hangup) => {
+                    if received.is_none() {
+                        return;
+                    }
+                }
+                _ = Shutdown::wait() => return,
+            }
+            match Config::reload().await {
+                Ok(()) => println!("Config reloaded"),
+                Err(err) => eprintln!("Config reload failed: {:?}", err),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::backup::{BACKUP_PATH, backup_now};
+    use crate::common::config::backup::BackupConfig;
+    use crate::common::config::poem::PoemConfig;
+    use crate::common::config::security::SecurityHeadersConfig;
+    use crate::common::security::SecurityHeaders;
+    use poem::http::header::CONTENT_SECURITY_POLICY;
+    use poem::http::{Method, StatusCode};
+    use poem::{Endpoint, EndpointExt, Request, Route, get, handler, post};
+
+    #[handler]
+    fn index() -> &'static str {
+        "index"
+    }
+
+    #[tokio::test]
+    async fn reload_applies_headers_and_tokens_but_not_listeners() {
+        let (current, _guard) = Config::install(Config::default()).await;
+        let app = Route::new()
+            .at("/", get(index))
+            .at(BACKUP_PATH, post(backup_now))
+            .with(SecurityHeaders);
+        let backup = || {
+            app.get_response(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri_str(BACKUP_PATH)
+                    .finish(),
+            )
+        };
+
+        assert_eq!(backup().await.status(), StatusCode::NOT_FOUND);
+
+        Config::replace(Config {
+            poem: Arc::new(PoemConfig {
+                shutdown_timeout_secs: current.poem.shutdown_timeout_secs + 1,
+                ..PoemConfig::default()
+            }),
+            security_headers: Arc::new(SecurityHeadersConfig {
+                content_security_policy: "default-src 'none'".to_string(),
+                ..SecurityHeadersConfig::default()
+            }),
+            metrics: Arc::new(MetricsConfig {
+                bearer_token: Some("scrape".to_string()),
+                ..MetricsConfig::default()
+            }),
+            backup: Arc::new(BackupConfig {
+                bearer_token: Some("backup".to_string()),
+                ..BackupConfig::default()
+            }),
+            ..Config::default()
+        })
+        .await
+        .unwrap();
+
+        let res = app
+            .get_response(Request::builder().uri_str("/").finish())
+            .await;
+        assert_eq!(
+            res.header(CONTENT_SECURITY_POLICY),
+            Some("default-src 'none'")
+        );
+        assert_eq!(backup().await.status(), StatusCode::UNAUTHORIZED);
+
+        let reloaded = Config::fetch().await.unwrap();
+        assert_eq!(reloaded.metrics.bearer_token.as_deref(), Some("scrape"));
+        assert_eq!(
+            reloaded.poem.shutdown_timeout_secs,
+            current.poem.shutdown_timeout_secs
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SecurityHeadersConfig {
+    /// `{nonce}` is replaced with the per-request script nonce.
+    pub content_security_policy: String,
+    /// Only sent over HTTPS.
+    pub strict_transport_security: Option<String>,
+    pub referrer_policy: String,
+    pub permissions_policy: String,
+}
+
+impl Default for SecurityHeadersConfig {
+    fn default() -> Self {
+        Self {
+            content_security_policy: [
+                "default-src 'self'",
+                // Vue compiles in-DOM templates at runtime, hence 'unsafe-eval'.
+                "script-src 'self' 'nonce-{nonce}' 'unsafe-eval' https://cdnjs.cloudflare.com",
+                "style-src 'self'",
+                "img-src 'self' data:",
+                "connect-src 'self'",
+                "object-src 'none'",
+                "base-uri 'self'",
+                "form-action 'self'",
+                "frame-ancestors 'none'",
+            ]
+            .join("; "),
+            strict_transport_security: Some("max-age=31536000; includeSubDomains".to_string()),
+            referrer_policy: "strict-origin-when-cross-origin".to_string(),
+            permissions_policy: "camera=(), microphone=(), geolocation=(), payment=()".to_string(),
+        }
+    }
+}
@@ -1,14 +1,29 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct SqliteConfig {
     pub path: String,
+    /// Extra database files by name, each opened through its own `ConnectionMarker`.
+    #[serde(default)]
+    pub databases: BTreeMap<String, String>,
+}
+
+impl SqliteConfig {
+    /// `None` is the main database at `path`.
+    pub fn path_for(&self, name: Option<&str>) -> Option<&str> {
+        match name {
+            None => Some(self.path.as_str()),
+            Some(name) => self.databases.get(name).map(String::as_str),
+        }
+    }
 }
 
 impl Default for SqliteConfig {
     fn default() -> Self {
         Self {
             path: "./sqlite.db".to_string(),
+            databases: BTreeMap::new(),
         }
     }
 }
@@ -0,0 +1,242 @@
+use crate::common::config::database::DatabaseBackend;
+use crate::common::config::poem::ListenerConfig;
+use crate::common::config::{Config, ConfigError};
+use error_stack::Report;
+use ipnet::IpNet;
+use std::net::{IpAddr, ToSocketAddrs};
+use std::path::Path;
+
+fn check_socket_address(problems: &mut Vec<String>, key: &str, address: &str, port: u16) {
+    if port == 0 {
+        problems.push(format!("{}: port must not be 0", key));
+    }
+    if (address, port).to_socket_addrs().is_err() {
+        problems.push(format!("{}: '{}' is not a valid address", key, address));
+    }
+}
+
+fn check_readable_file(problems: &mut Vec<String>, key: &str, path: &str) {
+    if std::fs::File::open(path).is_err() {
+        problems.push(format!("{}: '{}' is not readable", key, path));
+    }
+}
+
+fn check_writable_dir(problems: &mut Vec<String>, key: &str, dir: &Path) {
+    if !dir.is_dir() {
+        problems.push(format!(
+            "{}: directory '{}' does not exist",
+            key,
+            dir.display()
+        ));
+        return;
+    }
+    let probe = dir.join(format!(".little_poem_write_check_{}", std::process::id()));
+    match std::fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(probe);
+        }
+        Err(_) => problems.push(format!(
+            "{}: directory '{}' is not writable",
+            key,
+            dir.display()
+        )),
+    }
+}
+
+fn parent_dir(path: &str) -> &Path {
+    match Path::new(path).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+impl Config {
+    fn collect_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if self.poem.listeners.is_empty() {
+            problems.push("poem.listeners: at least one listener is required".to_string());
+        }
+        let has_tcp_listener = self
+            .poem
+            .listeners
+            .iter()
+            .any(|listener| matches!(listener, ListenerConfig::Tcp { .. }));
+        if self.poem.port.is_some() && !has_tcp_listener {
+            problems.push("poem.port: needs a tcp listener to apply to".to_string());
+        }
+        for (i, listener) in self.poem.listeners.iter().enumerate() {
+            let key = format!("poem.listeners[{}]", i);
+            match listener {
+                ListenerConfig::Tcp { address, port } => {
+                    check_socket_address(&mut problems, &key, address, *port);
+                }
+                ListenerConfig::Unix { path, .. } => {
+                    if path.is_empty() {
+                        problems.push(format!("{}: path must not be empty", key));
+                    } else {
+                        check_writable_dir(&mut problems, &key, parent_dir(path));
+                    }
+                }
+                ListenerConfig::Tls {
+                    address,
+                    port,
+                    cert_path,
+                    key_path,
+                    redirect_port,
+                } => {
+                    check_socket_address(&mut problems, &key, address, *port);
+                    check_readable_file(&mut problems, &format!("{}.cert_path", key), cert_path);
+                    check_readable_file(&mut problems, &format!("{}.key_path", key), key_path);
+                    if *redirect_port == Some(0) {
+                        problems.push(format!("{}.redirect_port: port must not be 0", key));
+                    }
+                    if *redirect_port == Some(*port) {
+                        problems.push(format!(
+                            "{}.redirect_port: must differ from the HTTPS port",
+                            key
+                        ));
+                    }
+                }
+            }
+        }
+        if self.poem.shutdown_timeout_secs == 0 {
+            problems.push("poem.shutdown_timeout_secs: must be greater than 0".to_string());
+        }
+
+        if self.sqlite.path.is_empty() {
+            problems.push("sqlite.path: must not be empty".to_string());
+        } else {
+            check_writable_dir(&mut problems, "sqlite.path", parent_dir(&self.sqlite.path));
+        }
+        for (name, path) in self.sqlite.databases.iter() {
+            let key = format!("sqlite.databases.{}", name);
+            if path.is_empty() {
+                problems.push(format!("{}: must not be empty", key));
+            } else if *path == self.sqlite.path {
+                problems.push(format!("{}: must differ from sqlite.path", key));
+            } else {
+                check_writable_dir(&mut problems, &key, parent_dir(path));
+            }
+        }
+
+        if self.database.backend == DatabaseBackend::Postgres {
+            if !cfg!(feature = "postgres") {
+                problems.push(
+                    "database.backend: 'postgres' needs a build with the postgres feature"
+                        .to_string(),
+                );
+            }
+            if self
+                .database
+                .postgres_url
+                .as_deref()
+                .unwrap_or_default()
+                .is_empty()
+            {
+                problems.push(
+                    "database.postgres_url: required when database.backend is 'postgres'"
+                        .to_string(),
+                );
+            }
+        }
+
+        if let Some(address) = self.metrics.address.as_ref()
+            && address.to_socket_addrs().is_err()
+        {
+            problems.push(format!(
+                "metrics.address: '{}' is not a valid address",
+                address
+            ));
+        }
+        if let Some(token) = self.metrics.bearer_token.as_ref()
+            && token.is_empty()
+        {
+            problems.push("metrics.bearer_token: must not be empty when set".to_string());
+        }
+        if self.metrics.address.is_none() && self.metrics.bearer_token.is_none() {
+            problems.push(
+                "metrics: set metrics.address or metrics.bearer_token, /metrics is not served publicly without a token"
+                    .to_string(),
+            );
+        }
+
+        if self.health.database_timeout_ms == 0 {
+            problems.push("health.database_timeout_ms: must be greater than 0".to_string());
+        }
+
+        for cidr in self.proxy.trusted.iter() {
+            if cidr.parse::<IpNet>().is_err() && cidr.parse::<IpAddr>().is_err() {
+                problems.push(format!("proxy.trusted: '{}' is not a valid CIDR", cidr));
+            }
+        }
+
+        if let Some(directory) = self.backup.directory.as_ref() {
+            check_writable_dir(&mut problems, "backup.directory", Path::new(directory));
+        }
+        if self.backup.interval_secs == 0 {
+            problems.push("backup.interval_secs: must be greater than 0".to_string());
+        }
+        if self.backup.retention == 0 {
+            problems.push("backup.retention: must be greater than 0".to_string());
+        }
+        if let Some(token) = self.backup.bearer_token.as_ref()
+            && token.is_empty()
+        {
+            problems.push("backup.bearer_token: must not be empty when set".to_string());
+        }
+
+        problems
+    }
+
+    /// Checks the values that deserialise fine but would only fail at bind or open time.
+    pub fn validate(&self) -> Result<(), Report<ConfigError>> {
+        let problems = self.collect_problems();
+        if problems.is_empty() {
+            return Ok(());
+        }
+
+        let mut report = Report::new(ConfigError::ValidationError);
+        for problem in problems {
+            report = report.attach(problem);
+        }
+        Err(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::config::metrics::MetricsConfig;
+    use std::sync::Arc;
+
+    #[test]
+    fn metrics_need_an_address_or_a_token() {
+        let has_metrics_problem = |config: &Config| {
+            config
+                .collect_problems()
+                .iter()
+                .any(|p| p.starts_with("metrics:"))
+        };
+
+        assert!(!has_metrics_problem(&Config::default()));
+
+        let public = Config {
+            metrics: Arc::new(MetricsConfig {
+                address: None,
+                bearer_token: None,
+            }),
+            ..Config::default()
+        };
+        assert!(has_metrics_problem(&public));
+
+        let token = Config {
+            metrics: Arc::new(MetricsConfig {
+                address: None,
+                bearer_token: Some("scrape".to_string()),
+            }),
+            ..Config::default()
+        };
+        assert!(!has_metrics_problem(&token));
+    }
+}
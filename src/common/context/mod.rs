@@ -4,7 +4,7 @@ use crate::common::config::Config;
 use error_stack::Report;
 use poem::http::StatusCode;
 use poem::{FromRequest, Request, RequestBody};
-use std::sync::Weak;
+use std::sync::Arc;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -43,7 +43,7 @@ pub trait FromContext: Sized + Send + Sync {
 }
 
 pub struct Context<'a> {
-    pub config: Weak<Config>,
+    pub config: Arc<Config>,
     pub req: &'a Request,
 }
 
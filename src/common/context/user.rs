@@ -1,13 +1,13 @@
 use crate::common::cache_local::CacheLocalRequestExt;
 use crate::common::config::Config;
-use crate::common::context::{Context, ContextError, Dep, FromContext};
+use crate::common::context::{Context, ContextError, FromContext};
 use crate::user::model::UserIdContext;
 use crate::user::service::UserCheckService;
 use error_stack::Report;
 use poem::http::StatusCode;
 use poem::{FromRequest, Request, RequestBody};
 use std::marker::PhantomData;
-use std::sync::{Arc, Weak};
+use std::sync::Arc;
 
 pub trait FromUserContext: Sized + Send + Sync {
     fn from_user_context(
@@ -17,7 +17,7 @@ pub trait FromUserContext: Sized + Send + Sync {
 
 pub struct UserContext<'a> {
     pub user_context: Arc<UserIdContext>,
-    pub config: Weak<Config>,
+    pub config: Arc<Config>,
     pub req: &'a Request,
 }
 
@@ -28,7 +28,7 @@ impl UserContext<'_> {
 
     pub async fn inject_global<T: FromContext>(&self) -> Result<T, Report<ContextError>> {
         let ctx = Box::pin(Context {
-            config: Weak::clone(&self.config),
+            config: Arc::clone(&self.config),
             req: self.req,
         });
         T::from_context(&ctx).await
@@ -54,6 +54,31 @@ pub trait UserContextDependencyFlag: Sized + Send + Sync {
 
 pub struct DefaultFlag;
 
+/// The user of this request, looked up once and cached for the rest of it.
+pub async fn fetch_user_id_context(
+    req: &Request,
+    config: &Arc<Config>,
+) -> poem::Result<Arc<UserIdContext>> {
+    let Some(once_user_id_context) = req.cache_local::<Arc<UserIdContext>>() else {
+        return Err(poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR));
+    };
+    let user_id_context = once_user_id_context
+        .0
+        .get_or_try_init(|| async {
+            let ctx = Box::pin(Context {
+                config: Arc::clone(config),
+                req,
+            });
+            let user_service: UserCheckService = ctx.inject().await.map_err(|e| {
+                let status_code = e.current_context().status_code();
+                poem::Error::from_string(status_code.1, status_code.0)
+            })?;
+            Ok::<_, poem::Error>(Arc::new(user_service.get_user_context().await))
+        })
+        .await?;
+    Ok(Arc::clone(user_id_context))
+}
+
 impl UserContextDependencyFlag for DefaultFlag {}
 
 pub struct UserDep<T, F = DefaultFlag>(pub T, pub Arc<UserIdContext>, pub PhantomData<F>)
@@ -66,30 +91,13 @@ where
     T: FromUserContext,
     F: UserContextDependencyFlag,
 {
-    async fn from_request(req: &'a Request, body: &mut RequestBody) -> poem::Result<Self> {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> poem::Result<Self> {
         let config = match Config::fetch().await {
             Ok(config) => config,
             Err(_) => return Err(poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)),
         };
         let flag = F::build_flag_data();
-        let user_id_context = match req.cache_local::<Arc<UserIdContext>>() {
-            None => {
-                return Err(poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR));
-            }
-            Some(once_user_id_context) => {
-                let v: Result<&Arc<UserIdContext>, poem::Error> = once_user_id_context
-                    .0
-                    .get_or_try_init(|| async {
-                        let user_service: Dep<UserCheckService> =
-                            Dep::from_request(req, body).await?;
-
-                        Ok(Arc::new(user_service.0.get_user_context()))
-                    })
-                    .await;
-                let v = v?;
-                Arc::clone(&v)
-            }
-        };
+        let user_id_context = fetch_user_id_context(req, &config).await?;
 
         if user_id_context.is_user && !flag.allow_user {
             return Err(poem::Error::from_status(StatusCode::UNAUTHORIZED));
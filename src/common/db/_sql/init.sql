@@ -4,7 +4,9 @@ CREATE TABLE users
 (
     id       INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
     username TEXT UNIQUE                       NOT NULL,
-    password BLOB                              NOT NULL
+    password BLOB                              NOT NULL,
+    locale    TEXT,
+    time_zone TEXT
 );
 
 CREATE TABLE user_login_tokens
@@ -0,0 +1,27 @@
+CREATE TABLE users
+(
+    id       BIGSERIAL PRIMARY KEY NOT NULL,
+    username TEXT UNIQUE           NOT NULL,
+    password BYTEA                 NOT NULL,
+    locale    TEXT,
+    time_zone TEXT
+);
+
+CREATE TABLE user_login_tokens
+(
+    user_id      BIGINT      NOT NULL,
+    token        TEXT UNIQUE NOT NULL,
+    expire_after TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE TABLE bucket_list
+(
+    id          BIGSERIAL PRIMARY KEY NOT NULL,
+    name        TEXT                  NOT NULL,
+    description TEXT                  NOT NULL,
+    timestamp   TIMESTAMPTZ           NOT NULL DEFAULT now()
+);
+
+INSERT INTO bucket_list (name, description)
+VALUES ('Hello World', 'Test');
@@ -0,0 +1,4 @@
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS locale TEXT;
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS time_zone TEXT;
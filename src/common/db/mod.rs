@@ -1,18 +1,112 @@
+use crate::common::config::Config;
 use crate::common::context::{Context, ContextError, FromContext};
 use crate::common::error::{ExtraResultExt, FromIntoStackError};
-use crate::common::password::Password;
+use crate::common::password::{Password, PasswordError};
 use error_stack::{Report, ResultExt};
-use rusqlite::{Connection, named_params};
+use rusqlite::{Connection, MAIN_DB, named_params};
+use std::any::TypeId;
+use std::collections::HashMap;
 use std::marker::PhantomData;
-use std::sync::{Arc, Mutex};
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
 use thiserror::Error;
 use tokio::sync::OnceCell;
 
-pub trait ConnectionMarker: Send + Sync {}
+#[cfg(feature = "postgres")]
+pub mod postgres;
+
+pub const SCHEMA_VERSION: i64 = 3;
+
+/// Applied in order to databases below the listed version, `init.sql` is always current.
+const MIGRATIONS: &[(i64, &str)] = &[
+    (2, include_str!("_sql/migrate/002_user_locale.sql")),
+    (3, include_str!("_sql/migrate/003_user_time_zone.sql")),
+];
+
+const DEFAULT_USERNAME: &str = "default";
+
+/// Seeded into every new database so a fresh install can log in.
+fn default_user_password() -> Result<Box<[u8]>, Report<PasswordError>> {
+    Password::hash_password("banana".to_string())
+        .attach_critical("Failed to hash password".to_string())?
+        .encode_to_msg_pack()
+        .attach_critical("Failed to encode password".to_string())
+}
+
+/// One marker type per database file, so each `SqliteClient<T>` is cached and injected
+/// on its own. A marker for a file under `sqlite.databases.audit` looks like:
+///
+/// ```ignore
+/// pub struct AuditConnection;
+///
+/// impl ConnectionMarker for AuditConnection {
+///     const NAME: Option<&'static str> = Some("audit");
+///
+///     fn prepare(conn: &Connection, created: bool) -> Result<(), Report<SqliteClientError>> {
+///         if created {
+///             conn.execute_batch(include_str!("_sql/audit_init.sql"))
+///                 .change_context(SqliteClientError::InitFailed)?;
+///         }
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait ConnectionMarker: Send + Sync + 'static {
+    /// Key under `sqlite.databases`, `None` is the main database at `sqlite.path`.
+    const NAME: Option<&'static str>;
+
+    /// Runs right after the file is opened, `created` is true when it did not exist yet.
+    fn prepare(_conn: &Connection, _created: bool) -> Result<(), Report<SqliteClientError>> {
+        Ok(())
+    }
+}
 
 pub struct DefaultConnection;
 
-impl ConnectionMarker for DefaultConnection {}
+impl ConnectionMarker for DefaultConnection {
+    const NAME: Option<&'static str> = None;
+
+    fn prepare(conn: &Connection, created: bool) -> Result<(), Report<SqliteClientError>> {
+        if created {
+            conn.execute_batch(include_str!("_sql/init.sql"))
+                .change_context(SqliteClientError::InitFailed)
+                .attach_critical("Init failed".to_string())?;
+
+            let password = default_user_password().change_context(SqliteClientError::InitFailed)?;
+
+            conn.execute(
+                include_str!("_sql/add_user.sql",),
+                named_params! {
+                    ":username": DEFAULT_USERNAME,
+                    ":password": password.to_vec(),
+                },
+            )
+            .change_context(SqliteClientError::InitFailed)
+            .attach_critical("Failed to create default user".to_string())?;
+
+            conn.pragma_update(None, "user_version", SCHEMA_VERSION)
+                .change_context(SqliteClientError::InitFailed)
+                .attach_critical("Failed to set schema version".to_string())?;
+        } else {
+            let version: i64 = conn
+                .pragma_query_value(None, "user_version", |row| row.get(0))
+                .change_context(SqliteClientError::InitFailed)
+                .attach_critical("Failed to read schema version".to_string())?;
+            // Databases created before schema versioning match version 1.
+            let version = version.max(1);
+            for (target, sql) in MIGRATIONS.iter().filter(|(target, _)| *target > version) {
+                conn.execute_batch(sql)
+                    .change_context(SqliteClientError::InitFailed)
+                    .attach_critical_lazy(|| format!("Migration to version {target} failed"))?;
+                conn.pragma_update(None, "user_version", target)
+                    .change_context(SqliteClientError::InitFailed)
+                    .attach_critical("Failed to set schema version".to_string())?;
+            }
+        }
+        Ok(())
+    }
+}
 
 #[derive(Error, Debug)]
 pub enum SqliteClientError {
@@ -22,14 +116,46 @@ pub enum SqliteClientError {
     Connection,
     #[error("Init failed")]
     InitFailed,
+    #[error("Lock error")]
+    LockError,
+    #[error("Query error")]
+    QueryError,
+    #[error("Close failed")]
+    CloseFailed,
+    #[error("Connection closed")]
+    Closed,
+    #[error("Backup failed")]
+    BackupFailed,
+    #[error("Database not configured")]
+    NotConfigured,
 }
 
 impl FromIntoStackError for SqliteClientError {}
 
-pub struct SqliteClient<T = DefaultConnection>(Arc<Mutex<Connection>>, PhantomData<T>)
+/// `None` once the client was closed.
+type SharedConnection = Arc<Mutex<Option<Connection>>>;
+
+pub struct SqliteClient<T = DefaultConnection>(SharedConnection, PhantomData<T>)
 where
     T: ConnectionMarker;
 
+/// The locked connection of a `SqliteClient`, only handed out while it is open.
+pub struct SqliteConnection<'a>(MutexGuard<'a, Option<Connection>>);
+
+impl Deref for SqliteConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref().expect("checked by SqliteClient::lock")
+    }
+}
+
+impl DerefMut for SqliteConnection<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0.as_mut().expect("checked by SqliteClient::lock")
+    }
+}
+
 impl<T: ConnectionMarker> SqliteClient<T> {
     pub fn new(sqlite_path: String) -> Result<Self, Report<SqliteClientError>> {
         if sqlite_path.is_empty() {
@@ -41,34 +167,63 @@ impl<T: ConnectionMarker> SqliteClient<T> {
         let conn = Connection::open(sqlite_path)
             .change_context(SqliteClientError::Connection)
             .attach_critical("Sqlite Connection failed".to_string())?;
-        if !file_exist {
-            conn.execute_batch(include_str!("_sql/init.sql"))
-                .change_context(SqliteClientError::InitFailed)
-                .attach_critical("Init failed".to_string())?;
+        T::prepare(&conn, !file_exist)?;
 
-            let password = Password::hash_password("banana".to_string())
-                .change_context(SqliteClientError::InitFailed)
-                .attach_critical("Failed to hash password".to_string())?
-                .encode_to_msg_pack()
-                .change_context(SqliteClientError::InitFailed)
-                .attach_critical("Failed to encode password".to_string())?;
+        Ok(SqliteClient(Arc::new(Mutex::new(Some(conn))), PhantomData))
+    }
 
-            conn.execute(
-                include_str!("_sql/add_user.sql",),
-                named_params! {
-                    ":username": "default",
-                    ":password": password.to_vec(),
-                },
-            )
-            .change_context(SqliteClientError::InitFailed)
-            .attach_critical("Failed to create default user".to_string())?;
+    /// Waits for the connection, fails with `Closed` after [`Self::close`].
+    pub fn lock(&self) -> Result<SqliteConnection<'_>, Report<SqliteClientError>> {
+        let guard = self
+            .0
+            .lock()
+            .map_err(|_| Report::new(SqliteClientError::LockError))?;
+        if guard.is_none() {
+            return Err(Report::new(SqliteClientError::Closed));
         }
+        Ok(SqliteConnection(guard))
+    }
+
+    pub fn ping(&self) -> Result<(), Report<SqliteClientError>> {
+        let conn = self.lock()?;
+        conn.query_row("SELECT 1", [], |row| row.get::<_, i64>(0))
+            .change_context(SqliteClientError::QueryError)?;
+        Ok(())
+    }
+
+    pub fn schema_version(&self) -> Result<i64, Report<SqliteClientError>> {
+        let conn = self.lock()?;
+        conn.pragma_query_value(None, "user_version", |row| row.get(0))
+            .change_context(SqliteClientError::QueryError)
+    }
 
-        Ok(SqliteClient(Arc::new(Mutex::new(conn)), PhantomData))
+    /// Copies a consistent snapshot of the live database to `path` with SQLite's online
+    /// backup API, writers wait on the connection lock until the copy is done.
+    pub fn backup_to(&self, path: &Path) -> Result<(), Report<SqliteClientError>> {
+        let conn = self.lock()?;
+        conn.backup(MAIN_DB, path, None)
+            .change_context(SqliteClientError::BackupFailed)
+            .attach_critical_lazy(|| format!("Unable to write backup '{}'", path.display()))
     }
 
-    pub fn get_conn(&self) -> &Mutex<Connection> {
-        self.0.as_ref()
+    /// Checkpoints any WAL and closes the underlying connection. Later use of this client,
+    /// or of its clones, fails with `Closed`, closing again does nothing.
+    pub fn close(&self) -> Result<(), Report<SqliteClientError>> {
+        let mut guard = self
+            .0
+            .lock()
+            .map_err(|_| Report::new(SqliteClientError::LockError))?;
+        let Some(conn) = guard.as_ref() else {
+            return Ok(());
+        };
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+            .change_context(SqliteClientError::CloseFailed)?;
+        if let Some(conn) = guard.take() {
+            conn.close().map_err(|(_, err)| {
+                Report::new(err).change_context(SqliteClientError::CloseFailed)
+            })?;
+        }
+        Ok(())
     }
 }
 
@@ -78,19 +233,162 @@ impl<T: ConnectionMarker> Clone for SqliteClient<T> {
     }
 }
 
-static SQLITE_CLIENT_CACHE: OnceCell<SqliteClient> = OnceCell::const_new();
+/// Connections by marker type, so every `SqliteClient<T>` shares one connection per file.
+/// The map lock is only held to find the cell, a slow open does not hold up other markers.
+static SQLITE_CLIENT_CACHE: LazyLock<Mutex<HashMap<TypeId, Arc<OnceCell<SharedConnection>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
 
-impl FromContext for SqliteClient {
-    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
-        let sqlite_client: Result<&Self, Report<ContextError>> = SQLITE_CLIENT_CACHE
+impl<T: ConnectionMarker> SqliteClient<T> {
+    /// Opens the database configured for `T` once, shared by request handlers and CLI commands.
+    pub async fn fetch(config: &Config) -> Result<Self, Report<SqliteClientError>> {
+        let cell = Arc::clone(
+            SQLITE_CLIENT_CACHE
+                .lock()
+                .map_err(|_| Report::new(SqliteClientError::LockError))?
+                .entry(TypeId::of::<T>())
+                .or_default(),
+        );
+        let conn = cell
             .get_or_try_init(|| async {
-                match ctx.config.upgrade() {
-                    None => Err(ContextError::Other.into()),
-                    Some(config) => Ok(Self::new(config.sqlite.path.clone())
-                        .change_context(ContextError::Other)?),
-                }
+                let path = config.sqlite.path_for(T::NAME).ok_or_else(|| {
+                    SqliteClientError::NotConfigured.into_stack_error_critical(format!(
+                        "No path set for 'sqlite.databases.{}'",
+                        T::NAME.unwrap_or_default()
+                    ))
+                })?;
+                Ok::<_, Report<SqliteClientError>>(Self::new(path.to_string())?.0)
             })
-            .await;
-        Ok(sqlite_client?.clone())
+            .await?;
+        Ok(Self(Arc::clone(conn), PhantomData))
+    }
+}
+
+impl SqliteClient {
+    /// Closes every cached database, not just the main one.
+    pub fn close_cached() -> Result<(), Report<SqliteClientError>> {
+        let cache = SQLITE_CLIENT_CACHE
+            .lock()
+            .map_err(|_| Report::new(SqliteClientError::LockError))?;
+        for conn in cache.values().filter_map(|cell| cell.get()) {
+            SqliteClient::<DefaultConnection>(Arc::clone(conn), PhantomData).close()?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: ConnectionMarker> FromContext for SqliteClient<T> {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Self::fetch(&ctx.config)
+            .await
+            .change_context(ContextError::Other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::config::sqlite::SqliteConfig;
+    use uuid::Uuid;
+
+    #[test]
+    fn closed_client_refuses_queries() {
+        let sqlite_client = SqliteClient::<DefaultConnection>::new(":memory:".to_string()).unwrap();
+        let clone = sqlite_client.clone();
+        sqlite_client.ping().unwrap();
+
+        sqlite_client.close().unwrap();
+        let err = clone.ping().unwrap_err();
+        assert!(matches!(err.current_context(), SqliteClientError::Closed));
+        // Shutdown may close the cache more than once.
+        sqlite_client.close().unwrap();
+    }
+
+    #[test]
+    fn old_databases_migrate_every_time_they_open() {
+        let path = std::env::temp_dir().join(format!("little_poem-{}.db", Uuid::new_v4()));
+        let sqlite_path = path.display().to_string();
+        SqliteClient::<DefaultConnection>::new(sqlite_path.clone())
+            .unwrap()
+            .close()
+            .unwrap();
+        // Back to the columns and version of the first release.
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "ALTER TABLE users DROP COLUMN locale;
+                 ALTER TABLE users DROP COLUMN time_zone;
+                 PRAGMA user_version = 1;",
+            )
+            .unwrap();
+
+        for _ in 0..2 {
+            let migrated = SqliteClient::<DefaultConnection>::new(sqlite_path.clone()).unwrap();
+            assert_eq!(migrated.schema_version().unwrap(), SCHEMA_VERSION);
+            let users: i64 = migrated
+                .lock()
+                .unwrap()
+                .query_row(
+                    "SELECT count(*) FROM users WHERE locale IS NULL AND time_zone IS NULL",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(users, 1);
+            migrated.close().unwrap();
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    struct FirstConnection;
+
+    impl ConnectionMarker for FirstConnection {
+        const NAME: Option<&'static str> = Some("first");
+    }
+
+    struct SecondConnection;
+
+    impl ConnectionMarker for SecondConnection {
+        const NAME: Option<&'static str> = Some("second");
+    }
+
+    #[tokio::test]
+    async fn markers_are_cached_separately() {
+        let config = Config {
+            sqlite: Arc::new(SqliteConfig {
+                path: ":memory:".to_string(),
+                databases: [("first", ":memory:"), ("second", ":memory:")]
+                    .into_iter()
+                    .map(|(name, path)| (name.to_string(), path.to_string()))
+                    .collect(),
+            }),
+            ..Config::default()
+        };
+        let (first, second) = tokio::join!(
+            SqliteClient::<FirstConnection>::fetch(&config),
+            SqliteClient::<SecondConnection>::fetch(&config),
+        );
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert!(!Arc::ptr_eq(&first.0, &second.0));
+
+        let again = SqliteClient::<FirstConnection>::fetch(&config)
+            .await
+            .unwrap();
+        assert!(Arc::ptr_eq(&first.0, &again.0));
+
+        first
+            .lock()
+            .unwrap()
+            .execute_batch("CREATE TABLE only_first (id INTEGER)")
+            .unwrap();
+        let table_count: i64 = second
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name = 'only_first'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(table_count, 0);
     }
 }
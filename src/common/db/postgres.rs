@@ -0,0 +1,202 @@
+use crate::common::config::Config;
+use crate::common::context::{Context, ContextError, FromContext};
+use crate::common::db::{DEFAULT_USERNAME, default_user_password};
+use crate::common::error::{ExtraResultExt, FromIntoStackError};
+use error_stack::{Report, ResultExt};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::{OnceCell, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use tokio_postgres::{Client, NoTls};
+
+#[derive(Error, Debug)]
+pub enum PostgresClientError {
+    #[error("Postgres url empty")]
+    UrlEmpty,
+    #[error("Connection error")]
+    Connection,
+    #[error("Init failed")]
+    InitFailed,
+    #[error("Query error")]
+    QueryError,
+}
+
+impl FromIntoStackError for PostgresClientError {}
+
+/// One connection shared by every request. Queries pipeline over it concurrently
+/// through a read guard, a transaction takes the write guard so nothing interleaves.
+pub struct PostgresClient(Arc<RwLock<Client>>);
+
+impl PostgresClient {
+    pub async fn new(url: &str) -> Result<Self, Report<PostgresClientError>> {
+        if url.is_empty() {
+            return Err(PostgresClientError::UrlEmpty
+                .into_stack_error_critical("Postgres url is empty".to_string()));
+        }
+        let (client, connection) = tokio_postgres::connect(url, NoTls)
+            .await
+            .change_context(PostgresClientError::Connection)
+            .attach_critical("Postgres connection failed".to_string())?;
+        tokio::spawn(async move {
+            if let Err(err) = connection.await {
+                eprintln!("Postgres connection error: {}", err);
+            }
+        });
+
+        let initialised = client
+            .query_one("SELECT to_regclass('users') IS NOT NULL", &[])
+            .await
+            .change_context(PostgresClientError::InitFailed)?
+            .get::<_, bool>(0);
+        if !initialised {
+            client
+                .batch_execute(include_str!("_sql/postgres/init.sql"))
+                .await
+                .change_context(PostgresClientError::InitFailed)
+                .attach_critical("Init failed".to_string())?;
+
+            let password =
+                default_user_password().change_context(PostgresClientError::InitFailed)?;
+            client
+                .execute(
+                    include_str!("_sql/postgres/add_user.sql"),
+                    &[&DEFAULT_USERNAME, &password.as_ref()],
+                )
+                .await
+                .change_context(PostgresClientError::InitFailed)
+                .attach_critical("Failed to create default user".to_string())?;
+        } else {
+            client
+                .batch_execute(include_str!("_sql/postgres/migrate.sql"))
+                .await
+                .change_context(PostgresClientError::InitFailed)
+                .attach_critical("Migration failed".to_string())?;
+        }
+
+        Ok(Self(Arc::new(RwLock::new(client))))
+    }
+
+    pub async fn get_client(&self) -> RwLockReadGuard<'_, Client> {
+        self.0.read().await
+    }
+
+    /// For transactions, waits for the queries in flight.
+    pub async fn get_client_mut(&self) -> RwLockWriteGuard<'_, Client> {
+        self.0.write().await
+    }
+
+    pub async fn ping(&self) -> Result<(), Report<PostgresClientError>> {
+        self.get_client()
+            .await
+            .query_one("SELECT 1", &[])
+            .await
+            .change_context(PostgresClientError::QueryError)?;
+        Ok(())
+    }
+}
+
+impl Clone for PostgresClient {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+static POSTGRES_CLIENT_CACHE: OnceCell<PostgresClient> = OnceCell::const_new();
+
+impl PostgresClient {
+    pub async fn fetch(config: &Config) -> Result<Self, Report<PostgresClientError>> {
+        let postgres_client: Result<&Self, Report<PostgresClientError>> = POSTGRES_CLIENT_CACHE
+            .get_or_try_init(|| async {
+                Self::new(config.database.postgres_url.as_deref().unwrap_or_default()).await
+            })
+            .await;
+        Ok(postgres_client?.clone())
+    }
+}
+
+impl FromContext for PostgresClient {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Self::fetch(&ctx.config)
+            .await
+            .change_context(ContextError::Other)
+    }
+}
+
+/// Points the tests at a Postgres server, the Postgres cases are skipped when it is unset.
+#[cfg(test)]
+const TEST_DATABASE_URL: &str = "LITTLE_POEM_TEST_DATABASE__URL";
+
+/// `url` with every new table created in `schema`.
+#[cfg(test)]
+fn with_search_path(url: &str, schema: &str) -> String {
+    if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+        let separator = if url.contains('?') { '&' } else { '?' };
+        format!("{url}{separator}options=-csearch_path%3D{schema}")
+    } else {
+        format!("{url} options='-csearch_path={schema}'")
+    }
+}
+
+/// A client on a new, empty database for one test: the tables go into the session's
+/// temporary schema, so they are not shared and are dropped with the connection.
+#[cfg(test)]
+pub async fn test_client() -> Option<PostgresClient> {
+    let url = std::env::var(TEST_DATABASE_URL).ok()?;
+    Some(
+        PostgresClient::new(&with_search_path(&url, "pg_temp"))
+            .await
+            .unwrap(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn old_databases_migrate_every_time_they_open() {
+        let Ok(url) = std::env::var(TEST_DATABASE_URL) else {
+            return;
+        };
+        // A real schema rather than pg_temp, so the second client sees the first one's tables.
+        let schema = format!("little_poem_test_{}", Uuid::new_v4().simple());
+        let (admin, connection) = tokio_postgres::connect(&url, NoTls).await.unwrap();
+        tokio::spawn(connection);
+        admin
+            .batch_execute(&format!("CREATE SCHEMA {schema}"))
+            .await
+            .unwrap();
+
+        let created = PostgresClient::new(&with_search_path(&url, &schema))
+            .await
+            .unwrap();
+        // Back to the columns of the first release.
+        created
+            .get_client()
+            .await
+            .batch_execute("ALTER TABLE users DROP COLUMN locale, DROP COLUMN time_zone")
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            let migrated = PostgresClient::new(&with_search_path(&url, &schema))
+                .await
+                .unwrap();
+            let users: i64 = migrated
+                .get_client()
+                .await
+                .query_one(
+                    "SELECT count(*) FROM users WHERE locale IS NULL AND time_zone IS NULL",
+                    &[],
+                )
+                .await
+                .unwrap()
+                .get(0);
+            assert_eq!(users, 1);
+        }
+
+        admin
+            .batch_execute(&format!("DROP SCHEMA {schema} CASCADE"))
+            .await
+            .unwrap();
+    }
+}
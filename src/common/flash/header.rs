@@ -0,0 +1,159 @@
+use crate::common::config::Config;
+use crate::common::context::user::{UserContext, fetch_user_id_context};
+use crate::common::flash::{Flash, FlashMessage, FlashView};
+use poem::http::HeaderValue;
+use poem::http::header::ACCEPT;
+use poem::i18n::Locale;
+use poem::session::Session;
+use poem::{Endpoint, IntoResponse, Request, Response};
+use std::sync::Arc;
+
+/// JSON array of [`FlashView`], with non-ASCII text as `\u` escapes.
+pub const FLASH_HEADER: &str = "x-flash";
+
+/// Requests made by scripts, `fetchJson` in `common.js` and HTMX both qualify.
+fn is_script_request(req: &Request) -> bool {
+    req.header("x-requested-with")
+        .is_some_and(|value| value.eq_ignore_ascii_case("xmlhttprequest"))
+        || req.header("hx-request").is_some()
+        || req
+            .header(ACCEPT)
+            .is_some_and(|value| value.contains("application/json"))
+}
+
+/// Header values are bytes, browsers read them as Latin-1.
+fn escape_non_ascii(json: &str) -> String {
+    let mut escaped = String::with_capacity(json.len());
+    for c in json.chars() {
+        if c.is_ascii() {
+            escaped.push(c);
+        } else {
+            for unit in c.encode_utf16(&mut [0; 2]) {
+                escaped.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+    escaped
+}
+
+fn flash_header_value(flashes: &[Flash], locale: &Locale) -> Option<HeaderValue> {
+    let views: Vec<FlashView> = flashes
+        .iter()
+        .map(|flash| flash.translate(locale))
+        .collect();
+    let json = serde_json::to_string(&views).ok()?;
+    HeaderValue::from_str(&escape_non_ascii(&json)).ok()
+}
+
+/// The locale pages use, a logged-in user's saved language first.
+async fn flash_locale(req: &Request) -> Option<Locale> {
+    let config = Arc::clone(req.data::<Arc<Config>>()?);
+    let user_context = fetch_user_id_context(req, &config).await.ok()?;
+    UserContext {
+        user_context,
+        config,
+        req,
+    }
+    .inject()
+    .await
+    .ok()
+}
+
+/// Moves the flashes queued while answering a script request into [`FLASH_HEADER`],
+/// as no page is rendered to show them. Redirects keep them for the next page.
+/// Must run inside `CookieSession`, so taking them is saved.
+pub async fn flash_header_middleware<E: Endpoint>(next: E, req: Request) -> poem::Result<Response> {
+    if !is_script_request(&req) {
+        return next.call(req).await.map(IntoResponse::into_response);
+    }
+    let session = req.data::<Session>().cloned();
+    let locale = flash_locale(&req).await;
+
+    let mut res = next.call(req).await?.into_response();
+    if res.status().is_redirection() {
+        return Ok(res);
+    }
+    if let (Some(session), Some(locale)) = (session, locale) {
+        let flashes = session.take_flashes();
+        if !flashes.is_empty()
+            && let Some(value) = flash_header_value(&flashes, &locale)
+        {
+            res.headers_mut().insert(FLASH_HEADER, value);
+        }
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_app;
+    use crate::common::config::sqlite::SqliteConfig;
+    use crate::home::route_home_page;
+    use crate::user::repository::user_repository;
+    use poem::http::header::{COOKIE, SET_COOKIE};
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn saved_locale_translates_the_header() {
+        let (config, _guard) = Config::install(Config {
+            sqlite: Arc::new(SqliteConfig {
+                path: ":memory:".to_string(),
+                ..SqliteConfig::default()
+            }),
+            ..Config::default()
+        })
+        .await;
+        let repo = user_repository(&config).await.unwrap();
+        let username = format!("flash-{}", Uuid::new_v4().simple());
+        repo.register_user(username.clone(), Box::new([]))
+            .await
+            .unwrap();
+        let id = repo.get_user_password(username).await.unwrap().id;
+        repo.update_user_locale(id, Some("fr-FR".to_string()))
+            .await
+            .unwrap();
+        let (first_token, second_token) = (Uuid::new_v4().to_string(), Uuid::new_v4().to_string());
+        repo.add_token(first_token.clone(), id).await.unwrap();
+        repo.add_token(second_token.clone(), id).await.unwrap();
+        let app = build_app(route_home_page(), &config).unwrap();
+
+        // Logging out queues a flash and redirects, so it stays in the session.
+        let res = app
+            .get_response(
+                Request::builder()
+                    .uri_str("/user/logout/")
+                    .header(COOKIE, format!("login-token={}", first_token))
+                    .finish(),
+            )
+            .await;
+        assert!(res.status().is_redirection());
+        assert!(res.headers().get(FLASH_HEADER).is_none());
+        let session_cookie = res
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| value.split(';').next())
+            .find(|cookie| cookie.starts_with("poem-session="))
+            .unwrap()
+            .to_string();
+
+        // No locale cookie, only the language saved on the user.
+        let res = app
+            .get_response(
+                Request::builder()
+                    .uri_str("/bucket-list/all")
+                    .header("x-requested-with", "XMLHttpRequest")
+                    .header(
+                        COOKIE,
+                        format!("{}; login-token={}", session_cookie, second_token),
+                    )
+                    .finish(),
+            )
+            .await;
+        let header = res.headers().get(FLASH_HEADER).unwrap().to_str().unwrap();
+        let views: serde_json::Value = serde_json::from_str(header).unwrap();
+        assert_eq!(views[0]["message"], "Déconnexion réussie");
+    }
+}
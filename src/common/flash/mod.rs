@@ -1,83 +1,207 @@
+pub mod header;
+
 use crate::common::context::{Context, ContextError, FromContext};
+use crate::common::embed::{Asset, EmbedAsString};
+use crate::common::locale::LocaleExtForText;
 use error_stack::Report;
 use maud::{Markup, html};
+use poem::i18n::{I18NArgs, Locale};
 use poem::session::Session;
 use serde::{Deserialize, Serialize};
 
+const FLASH_SESSION_KEY: &str = "flash";
+
+/// How long success and info flashes stay before dismissing themselves.
+pub const DEFAULT_FLASH_TIMEOUT_MS: u32 = 5000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FlashSeverity {
+    Success,
+    Info,
+    Warning,
+    Error,
+}
+
+impl FlashSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// `title` and `msg` are FTL keys, translated with `args` when the flash is shown.
 #[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(tag = "type")]
-pub enum Flash {
-    Success { msg: String },
-    Error { msg: String },
-    Warning { msg: String },
+pub struct Flash {
+    pub severity: FlashSeverity,
+    pub title: Option<String>,
+    pub msg: Option<String>,
+    pub args: Vec<(String, String)>,
+    /// Milliseconds before it dismisses itself, `None` keeps it until closed.
+    pub timeout: Option<u32>,
 }
 
 impl Flash {
-    pub fn as_html(&self) -> Markup {
-        match self {
-            Self::Success { msg } => {
-                html! {
-                   div .flash-message .flash-message-success {
-                       (msg)
-                   }
-                }
-            }
-            Self::Error { msg } => {
-                html! {
-                   div .flash-message .flash-message-error {
-                       (msg)
-                   }
-                }
-            }
-            Self::Warning { msg } => {
-                html! {
-                   div .flash-message .flash-message-warning {
-                       (msg)
-                   }
+    /// Warnings and errors stay until closed, the others use [`DEFAULT_FLASH_TIMEOUT_MS`].
+    pub fn new(severity: FlashSeverity, msg: &str) -> Self {
+        let timeout = match severity {
+            FlashSeverity::Success | FlashSeverity::Info => Some(DEFAULT_FLASH_TIMEOUT_MS),
+            FlashSeverity::Warning | FlashSeverity::Error => None,
+        };
+        Self {
+            severity,
+            title: None,
+            msg: Some(msg.to_string()),
+            args: Vec::new(),
+            timeout,
+        }
+    }
+
+    pub fn success(msg: &str) -> Self {
+        Self::new(FlashSeverity::Success, msg)
+    }
+
+    pub fn info(msg: &str) -> Self {
+        Self::new(FlashSeverity::Info, msg)
+    }
+
+    pub fn warning(msg: &str) -> Self {
+        Self::new(FlashSeverity::Warning, msg)
+    }
+
+    pub fn error(msg: &str) -> Self {
+        Self::new(FlashSeverity::Error, msg)
+    }
+
+    pub fn attach_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// For a flash that only has a title.
+    pub fn remove_msg(mut self) -> Self {
+        self.msg = None;
+        self
+    }
+
+    pub fn attach_arg(mut self, name: &str, value: &str) -> Self {
+        self.args.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn set_timeout(mut self, timeout: Option<u32>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn translate_key(&self, locale: &Locale, id: &str) -> String {
+        locale.translate_with_args(id, || {
+            self.args
+                .iter()
+                .fold(I18NArgs::default(), |args, (name, value)| {
+                    args.set(name.as_str(), value.as_str())
+                })
+        })
+    }
+
+    pub fn translate(&self, locale: &Locale) -> FlashView {
+        FlashView {
+            severity: self.severity,
+            title: self.title.as_ref().map(|id| self.translate_key(locale, id)),
+            message: self.msg.as_ref().map(|id| self.translate_key(locale, id)),
+            timeout: self.timeout,
+        }
+    }
+}
+
+/// A translated flash, rendered into the page or sent in the `X-Flash` header.
+#[derive(Serialize)]
+pub struct FlashView {
+    pub severity: FlashSeverity,
+    pub title: Option<String>,
+    pub message: Option<String>,
+    pub timeout: Option<u32>,
+}
+
+impl FlashView {
+    pub fn as_html(&self, dismiss_label: &str) -> Markup {
+        html! {
+            div class={ "flash-message flash-message-" (self.severity.as_str()) }
+                role="alert" data-timeout=[self.timeout] {
+                div .flash-body {
+                    @if let Some(title) = self.title.as_ref() {
+                        strong .flash-title { (title) }
+                    }
+                    @if let Some(message) = self.message.as_ref() {
+                        span .flash-text { (message) }
+                    }
                 }
+                button .flash-dismiss type="button" aria-label=(dismiss_label) { "×" }
             }
         }
     }
 }
 
+/// Binds the dismiss buttons and timeouts of the rendered flashes.
+pub fn flash_js() -> String {
+    if cfg!(debug_assertions) {
+        Asset::get("js/flash.js").as_string()
+    } else {
+        Asset::get("js/flash.min.js").as_string()
+    }
+}
+
 pub trait FlashMessageHtml {
-    fn flash_message_html(&self) -> Markup;
+    fn flash_message_html(&self, locale: &Locale) -> Markup;
 }
 
-impl FlashMessageHtml for Option<Flash> {
-    fn flash_message_html(&self) -> Markup {
-        match self {
-            None => {
-                html! {}
+/// The stack is rendered even when empty so scripts can add to it.
+impl FlashMessageHtml for [Flash] {
+    fn flash_message_html(&self, locale: &Locale) -> Markup {
+        let dismiss_label = locale.translate("flash-dismiss");
+        html! {
+            div .flash-stack #flash-stack data-dismiss-label=(dismiss_label) {
+                @for flash in self.iter() {
+                    (flash.translate(locale).as_html(&dismiss_label))
+                }
             }
-            Some(flash) => flash.as_html(),
         }
     }
 }
 
 pub trait FlashMessage {
+    /// Queues `flash` after the ones not shown yet.
     fn flash(&self, flash: Flash);
 
-    fn get_flash(&self) -> Option<Flash>;
+    /// Every queued flash, oldest first, leaving the queue empty.
+    fn take_flashes(&self) -> Vec<Flash>;
 }
 
 impl FlashMessage for Session {
     fn flash(&self, flash: Flash) {
-        self.set("flash", flash)
+        let mut flashes: Vec<Flash> = self.get(FLASH_SESSION_KEY).unwrap_or_default();
+        flashes.push(flash);
+        self.set(FLASH_SESSION_KEY, flashes)
     }
 
-    fn get_flash(&self) -> Option<Flash> {
-        let flash = self.get("flash");
-        self.remove("flash");
-        flash
+    fn take_flashes(&self) -> Vec<Flash> {
+        let flashes = self.get(FLASH_SESSION_KEY);
+        if flashes.is_some() {
+            self.remove(FLASH_SESSION_KEY);
+        }
+        flashes.unwrap_or_default()
     }
 }
 
-impl FromContext for Option<Flash> {
+impl FromContext for Vec<Flash> {
     async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
         Ok(match ctx.req.data::<Session>() {
-            None => None,
-            Some(session) => session.get_flash(),
+            None => Vec::new(),
+            Some(session) => session.take_flashes(),
         })
     }
 }
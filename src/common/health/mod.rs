@@ -0,0 +1,176 @@
+use crate::common::config::Config;
+use crate::common::config::database::DatabaseBackend;
+use crate::common::context::Context;
+use crate::common::db::{SCHEMA_VERSION, SqliteClient};
+use poem::http::StatusCode;
+use poem::web::Json;
+use poem::{IntoResponse, Request, Response, handler};
+use serde::Serialize;
+use serde_json::json;
+use std::time::Duration;
+use tokio::task::spawn_blocking;
+use tokio::time::timeout;
+
+pub const HEALTHZ_PATH: &str = "/healthz";
+pub const READYZ_PATH: &str = "/readyz";
+
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum CheckStatus {
+    Ok,
+    Failed { reason: String },
+}
+
+impl CheckStatus {
+    fn failed(reason: impl Into<String>) -> Self {
+        Self::Failed {
+            reason: reason.into(),
+        }
+    }
+
+    fn is_ok(&self) -> bool {
+        matches!(self, Self::Ok)
+    }
+}
+
+#[derive(Serialize)]
+struct ReadinessChecks {
+    config: CheckStatus,
+    database: CheckStatus,
+    schema: CheckStatus,
+}
+
+impl ReadinessChecks {
+    fn is_ready(&self) -> bool {
+        self.config.is_ok() && self.database.is_ok() && self.schema.is_ok()
+    }
+}
+
+#[handler]
+pub async fn healthz() -> Json<serde_json::Value> {
+    Json(json!({"status": "ok"}))
+}
+
+#[handler]
+pub async fn readyz(req: &Request) -> Response {
+    let checks = readiness_checks(req).await;
+    let status = if checks.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    Json(json!({
+        "status": if checks.is_ready() { "ready" } else { "not_ready" },
+        "checks": checks,
+    }))
+    .with_status(status)
+    .into_response()
+}
+
+async fn readiness_checks(req: &Request) -> ReadinessChecks {
+    let not_checked = || CheckStatus::failed("Not checked, config unavailable");
+
+    let config = match Config::fetch().await {
+        Ok(config) => config,
+        Err(err) => {
+            return ReadinessChecks {
+                config: CheckStatus::failed(err.to_string()),
+                database: not_checked(),
+                schema: not_checked(),
+            };
+        }
+    };
+    let database_timeout = Duration::from_millis(config.health.database_timeout_ms);
+    let (database, schema) = match config.database.backend {
+        DatabaseBackend::Sqlite => sqlite_checks(Context { config, req }, database_timeout).await,
+        #[cfg(feature = "postgres")]
+        DatabaseBackend::Postgres => {
+            postgres_checks(Context { config, req }, database_timeout).await
+        }
+        #[cfg(not(feature = "postgres"))]
+        DatabaseBackend::Postgres => (
+            CheckStatus::failed("Built without the postgres feature"),
+            CheckStatus::failed("Not checked, database unavailable"),
+        ),
+    };
+
+    ReadinessChecks {
+        config: CheckStatus::Ok,
+        database,
+        schema,
+    }
+}
+
+async fn sqlite_checks(ctx: Context<'_>, database_timeout: Duration) -> (CheckStatus, CheckStatus) {
+    let sqlite_client: SqliteClient = match ctx.inject().await {
+        Ok(sqlite_client) => sqlite_client,
+        Err(err) => {
+            return (
+                CheckStatus::failed(err.to_string()),
+                CheckStatus::failed("Not checked, database unavailable"),
+            );
+        }
+    };
+
+    match timeout(
+        database_timeout,
+        spawn_blocking(move || {
+            sqlite_client.ping()?;
+            sqlite_client.schema_version()
+        }),
+    )
+    .await
+    {
+        Ok(Ok(Ok(version))) if version == SCHEMA_VERSION => (CheckStatus::Ok, CheckStatus::Ok),
+        Ok(Ok(Ok(version))) => (
+            CheckStatus::Ok,
+            CheckStatus::failed(format!(
+                "Schema version is {version}, expected {SCHEMA_VERSION}"
+            )),
+        ),
+        Ok(Ok(Err(err))) => (
+            CheckStatus::failed(err.to_string()),
+            CheckStatus::failed("Not checked, database unavailable"),
+        ),
+        Ok(Err(err)) => (
+            CheckStatus::failed(err.to_string()),
+            CheckStatus::failed("Not checked, database unavailable"),
+        ),
+        Err(_) => (
+            CheckStatus::failed(format!(
+                "Timed out after {}ms",
+                database_timeout.as_millis()
+            )),
+            CheckStatus::failed("Not checked, database unavailable"),
+        ),
+    }
+}
+
+/// The Postgres schema is created in place on first connect, so only the connection is checked.
+#[cfg(feature = "postgres")]
+async fn postgres_checks(
+    ctx: Context<'_>,
+    database_timeout: Duration,
+) -> (CheckStatus, CheckStatus) {
+    use crate::common::db::postgres::PostgresClient;
+
+    let ping = async {
+        let postgres_client: PostgresClient = ctx.inject().await.map_err(|e| e.to_string())?;
+        postgres_client.ping().await.map_err(|e| e.to_string())
+    };
+    match timeout(database_timeout, ping).await {
+        Ok(Ok(())) => (CheckStatus::Ok, CheckStatus::Ok),
+        Ok(Err(err)) => (
+            CheckStatus::failed(err),
+            CheckStatus::failed("Not checked, database unavailable"),
+        ),
+        Err(_) => (
+            CheckStatus::failed(format!(
+                "Timed out after {}ms",
+                database_timeout.as_millis()
+            )),
+            CheckStatus::failed("Not checked, database unavailable"),
+        ),
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+pub enum AccessibilityProblem {
+    MissingLang,
+    MissingMain,
+    MissingSkipLink,
+    UnnamedNavigation,
+    UnlabelledField { name: String },
+    MissingDescription { id: String },
+    SilentFlash,
+}
+
+impl Display for AccessibilityProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingLang => write!(f, "<html> has no lang"),
+            Self::MissingMain => write!(f, "no single <main> landmark"),
+            Self::MissingSkipLink => write!(f, "no skip link to the main content"),
+            Self::UnnamedNavigation => write!(f, "<nav> has no aria-label"),
+            Self::UnlabelledField { name } => write!(f, "field '{}' has no label", name),
+            Self::MissingDescription { id } => {
+                write!(f, "aria-describedby points to missing '{}'", id)
+            }
+            Self::SilentFlash => write!(f, "flash message is not an alert"),
+        }
+    }
+}
+
+struct Tag<'a> {
+    name: String,
+    attributes: Vec<(&'a str, &'a str)>,
+}
+
+impl Tag<'_> {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| *value)
+    }
+
+    fn has_class(&self, class: &str) -> bool {
+        self.attribute("class")
+            .is_some_and(|classes| classes.split_whitespace().any(|c| c == class))
+    }
+}
+
+fn parse_attributes(mut source: &str) -> Vec<(&str, &str)> {
+    let mut attributes = Vec::new();
+    loop {
+        source = source.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        let Some(end) = source.find(|c: char| c.is_whitespace() || c == '=') else {
+            if !source.is_empty() {
+                attributes.push((source, ""));
+            }
+            return attributes;
+        };
+        let name = &source[..end];
+        source = source[end..].trim_start();
+        if let Some(rest) = source.strip_prefix("=\"") {
+            let value_end = rest.find('"').unwrap_or(rest.len());
+            attributes.push((name, &rest[..value_end]));
+            source = rest.get(value_end + 1..).unwrap_or_default();
+        } else {
+            attributes.push((name, ""));
+        }
+    }
+}
+
+/// Opening tags in order. Relies on maud quoting every attribute value and
+/// escaping `>` inside them, script bodies are skipped.
+fn opening_tags(html: &str) -> Vec<Tag<'_>> {
+    let mut tags = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        if rest.starts_with('/') || rest.starts_with('!') {
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let source = &rest[..end];
+        let name_end = source
+            .find(|c: char| c.is_whitespace() || c == '/')
+            .unwrap_or(source.len());
+        let name = source[..name_end].to_ascii_lowercase();
+        tags.push(Tag {
+            attributes: parse_attributes(&source[name_end..]),
+            name: name.clone(),
+        });
+        rest = &rest[end + 1..];
+        if name == "script" {
+            match rest.find("</script>") {
+                Some(close) => rest = &rest[close..],
+                None => break,
+            }
+        }
+    }
+    tags
+}
+
+fn is_field(tag: &Tag) -> bool {
+    match tag.name.as_str() {
+        "select" | "textarea" => true,
+        "input" => !matches!(
+            tag.attribute("type").unwrap_or("text"),
+            "hidden" | "submit" | "button" | "reset"
+        ),
+        _ => false,
+    }
+}
+
+/// Checks a rendered page for the properties every `ContextHtmlBuilder` page should have.
+pub fn audit_page(html: &str) -> Vec<AccessibilityProblem> {
+    let tags = opening_tags(html);
+    let mut problems = Vec::new();
+
+    let ids: HashSet<&str> = tags.iter().filter_map(|tag| tag.attribute("id")).collect();
+    let label_targets: HashSet<&str> = tags
+        .iter()
+        .filter(|tag| tag.name == "label")
+        .filter_map(|tag| tag.attribute("for"))
+        .collect();
+
+    let has_lang = tags.iter().any(|tag| {
+        tag.name == "html" && tag.attribute("lang").is_some_and(|lang| !lang.is_empty())
+    });
+    if !has_lang {
+        problems.push(AccessibilityProblem::MissingLang);
+    }
+
+    let mains: Vec<&Tag> = tags.iter().filter(|tag| tag.name == "main").collect();
+    if mains.len() != 1 {
+        problems.push(AccessibilityProblem::MissingMain);
+    }
+    // The skip link has to come before the navigation and any field.
+    let main_href = mains
+        .first()
+        .and_then(|main| main.attribute("id"))
+        .map(|id| format!("#{}", id));
+    let first_stop = tags
+        .iter()
+        .find(|tag| tag.name == "a" || tag.name == "nav" || is_field(tag));
+    let has_skip_link = first_stop.is_some_and(|tag| {
+        tag.name == "a" && main_href.is_some() && tag.attribute("href") == main_href.as_deref()
+    });
+    if !has_skip_link {
+        problems.push(AccessibilityProblem::MissingSkipLink);
+    }
+
+    for tag in tags.iter() {
+        if tag.name == "nav" && tag.attribute("aria-label").is_none() {
+            problems.push(AccessibilityProblem::UnnamedNavigation);
+        }
+        if tag.has_class("flash-message") && !matches!(tag.attribute("role"), Some("alert")) {
+            problems.push(AccessibilityProblem::SilentFlash);
+        }
+        if is_field(tag) {
+            let labelled = tag.attribute("aria-label").is_some()
+                || tag.attribute("aria-labelledby").is_some()
+                || tag
+                    .attribute("id")
+                    .is_some_and(|id| label_targets.contains(id));
+            if !labelled {
+                problems.push(AccessibilityProblem::UnlabelledField {
+                    name: tag.attribute("name").unwrap_or("?").to_string(),
+                });
+            }
+        }
+        if let Some(described_by) = tag.attribute("aria-describedby") {
+            for id in described_by.split_whitespace() {
+                if !ids.contains(id) {
+                    problems.push(AccessibilityProblem::MissingDescription { id: id.to_string() });
+                }
+            }
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_app;
+    use crate::common::config::Config;
+    use crate::common::config::sqlite::SqliteConfig;
+    use crate::common::locale::{LOCALE_COOKIE, available_languages};
+    use crate::home::route_home_page;
+    use crate::user::repository::user_repository;
+    use poem::http::StatusCode;
+    use poem::http::header::{COOKIE, SET_COOKIE};
+    use poem::{Endpoint, Request};
+    use std::sync::Arc;
+    use tokio::sync::MutexGuard;
+    use uuid::Uuid;
+
+    /// Pages a visitor can open.
+    const VISITOR_PAGES: &[&str] = &[
+        "/",
+        "/bucket-list/",
+        "/user/",
+        "/user/login/",
+        "/user/register/",
+    ];
+
+    /// Pages a logged-in user can open.
+    const USER_PAGES: &[&str] = &["/", "/bucket-list/", "/user/"];
+
+    async fn test_config() -> (Arc<Config>, MutexGuard<'static, ()>) {
+        Config::install(Config {
+            sqlite: Arc::new(SqliteConfig {
+                path: ":memory:".to_string(),
+                ..SqliteConfig::default()
+            }),
+            ..Config::default()
+        })
+        .await
+    }
+
+    /// A login token for the default user, as a cookie.
+    async fn log_in(config: &Config) -> String {
+        let repo = user_repository(config).await.unwrap();
+        let id = repo
+            .get_user_password("default".to_string())
+            .await
+            .unwrap()
+            .id;
+        let token = Uuid::new_v4().to_string();
+        repo.add_token(token.clone(), id).await.unwrap();
+        format!("login-token={}", token)
+    }
+
+    /// The response and the cookies it sets, as `name=value`.
+    async fn get(
+        app: &impl Endpoint,
+        page: &str,
+        cookies: &[String],
+    ) -> (StatusCode, String, Vec<String>) {
+        let req = Request::builder()
+            .uri_str(page)
+            .header(COOKIE, cookies.join("; "))
+            .finish();
+        let res = app.get_response(req).await;
+        let status = res.status();
+        let set_cookies = res
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| value.split(';').next())
+            .map(str::to_string)
+            .collect();
+        let html = res.into_body().into_string().await.unwrap();
+        (status, html, set_cookies)
+    }
+
+    fn assert_accessible(label: &str, html: &str) {
+        let problems: Vec<String> = audit_page(html)
+            .iter()
+            .map(|problem| problem.to_string())
+            .collect();
+        assert!(problems.is_empty(), "{}: {}", label, problems.join(", "));
+    }
+
+    async fn audit_pages(app: &impl Endpoint, pages: &[&str], cookies: &[String]) {
+        for language in available_languages() {
+            let mut cookies = cookies.to_vec();
+            cookies.push(format!("{}={}", LOCALE_COOKIE, language.id));
+            for page in pages {
+                let (status, html, _) = get(app, page, &cookies).await;
+                let label = format!("{} {}", language.id, page);
+                assert_eq!(status, StatusCode::OK, "{}", label);
+                assert_accessible(&label, &html);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn visitor_pages_are_accessible() {
+        let (config, _guard) = test_config().await;
+        let app = build_app(route_home_page(), &config).unwrap();
+        audit_pages(&app, VISITOR_PAGES, &[]).await;
+    }
+
+    #[tokio::test]
+    async fn user_pages_are_accessible() {
+        let (config, _guard) = test_config().await;
+        let login_cookie = log_in(&config).await;
+        let app = build_app(route_home_page(), &config).unwrap();
+        let (_, html, _) = get(&app, "/user/", std::slice::from_ref(&login_cookie)).await;
+        assert!(
+            html.contains(r#"id="account""#),
+            "/user/ shows the account form"
+        );
+        audit_pages(&app, USER_PAGES, &[login_cookie]).await;
+    }
+
+    #[tokio::test]
+    async fn queued_flash_is_an_alert() {
+        let (config, _guard) = test_config().await;
+        let app = build_app(route_home_page(), &config).unwrap();
+        for language in available_languages() {
+            let locale_cookie = format!("{}={}", LOCALE_COOKIE, language.id);
+            // Logging out queues a flash for the page it redirects to.
+            let (status, _, set_cookies) = get(
+                &app,
+                "/user/logout/",
+                &[log_in(&config).await, locale_cookie.clone()],
+            )
+            .await;
+            assert!(status.is_redirection(), "{} logout", language.id);
+
+            let mut cookies: Vec<String> = set_cookies
+                .into_iter()
+                .filter(|cookie| !cookie.starts_with("login-token="))
+                .collect();
+            cookies.push(locale_cookie);
+            let (status, html, _) = get(&app, "/user/", &cookies).await;
+            let label = format!("{} /user/ with a flash", language.id);
+            assert_eq!(status, StatusCode::OK, "{}", label);
+            assert!(html.contains("flash-message-success"), "{}", label);
+            assert_accessible(&label, &html);
+        }
+    }
+}
@@ -1,73 +1,96 @@
 use crate::common::context::user::{FromUserContext, UserContext};
 use crate::common::context::{Context, ContextError, FromContext};
-use crate::common::flash::{Flash, FlashMessageHtml};
+use crate::common::flash::{Flash, FlashMessageHtml, flash_js};
 use crate::common::html::HtmlBuilder;
+use crate::common::html::form::{FormBuilder, FormField};
+use crate::common::html::navigation::{NavigationItem, NavigationSection, navigation_items};
+use crate::common::html::theme::Theme;
+use crate::common::locale::format::LocaleExtForFormat;
+use crate::common::locale::{
+    LocaleExtForText, available_languages, cookie_language, user_language,
+};
+use crate::common::security::CspNonce;
 use crate::user::model::UserIdContext;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use error_stack::Report;
-use maud::{Markup, PreEscaped, html};
-use poem::i18n::Locale;
+use maud::{Markup, html};
+use poem::i18n::{I18NArgs, Locale};
+use poem::web::CsrfToken;
 use std::sync::{Arc, RwLock};
 
-pub struct NavigationItem {
-    name: String,
-    url: String,
-    tag: String,
-}
-
-impl NavigationItem {
-    fn navigations() -> Box<[Self]> {
-        [
-            Self {
-                name: "Home".to_string(),
-                url: "/".to_string(),
-                tag: "home".to_string(),
-            },
-            Self {
-                name: "Bucket List".to_string(),
-                url: "/bucket-list/".to_string(),
-                tag: "bucket-list".to_string(),
-            },
-            Self {
-                name: "User".to_string(),
-                url: "/user/".to_string(),
-                tag: "user".to_string(),
-            },
-        ]
-        .into()
-    }
-}
-
 struct ContextHtmlCellData {
     title: Option<String>,
     content: Option<Markup>,
     head: Option<Markup>,
     footer: Option<Markup>,
+    scripts: Vec<String>,
     current_tag: String,
 }
 
 pub struct ContextHtmlBuilder {
-    flash: Option<Flash>,
+    flashes: Vec<Flash>,
     user_id_context: Option<Arc<UserIdContext>>,
     data: RwLock<ContextHtmlCellData>,
+    nonce: Option<CspNonce>,
     pub locale: Locale,
+    /// The switcher's choice, `None` when `Accept-Language` decides.
+    language: Option<String>,
+    csrf_token: Option<CsrfToken>,
+    current_path: String,
+    theme: Theme,
+    /// UTC unless the logged-in user picked another.
+    pub time_zone: Tz,
 }
 
 impl ContextHtmlBuilder {
-    pub fn new(flash: Option<Flash>, locale: Locale) -> Self {
+    pub fn new(flashes: Vec<Flash>, locale: Locale, nonce: Option<CspNonce>) -> Self {
         Self {
-            flash,
+            flashes,
             user_id_context: None,
             data: RwLock::new(ContextHtmlCellData {
                 title: None,
                 content: None,
                 head: None,
                 footer: None,
+                scripts: Vec::new(),
                 current_tag: "".to_string(),
             }),
+            nonce,
             locale,
+            language: None,
+            csrf_token: None,
+            current_path: "/".to_string(),
+            theme: Theme::System,
+            time_zone: Tz::UTC,
         }
     }
 
+    pub fn format_date(&self, time: &DateTime<Utc>) -> String {
+        self.locale.format_date(time, self.time_zone)
+    }
+
+    pub fn format_datetime(&self, time: &DateTime<Utc>) -> String {
+        self.locale.format_datetime(time, self.time_zone)
+    }
+
+    pub fn format_relative(&self, time: &DateTime<Utc>) -> String {
+        self.locale.format_relative(time, &Utc::now())
+    }
+
+    pub fn format_integer(&self, value: i64) -> String {
+        self.locale.format_integer(value)
+    }
+
+    pub fn format_decimal(&self, value: f64, decimals: usize) -> String {
+        self.locale.format_decimal(value, decimals)
+    }
+
+    /// A form with this request's CSRF token already attached.
+    pub fn form(&self, id: &str, submit_label: String) -> FormBuilder {
+        FormBuilder::new(id, submit_label).attach_csrf_token(self.csrf_token.clone())
+    }
+
     pub fn set_user_id_context(&mut self, user_id_context: Arc<UserIdContext>) {
         self.user_id_context = Some(user_id_context);
     }
@@ -112,6 +135,13 @@ impl ContextHtmlBuilder {
         self
     }
 
+    pub fn attach_script(&self, script: String) -> &Self {
+        if let Ok(mut data) = self.data.try_write() {
+            data.scripts.push(script);
+        }
+        self
+    }
+
     pub fn set_current_tag(&self, tag: &str) -> &Self {
         match self.data.try_write() {
             Ok(mut data) => {
@@ -125,15 +155,24 @@ impl ContextHtmlBuilder {
     pub fn build(&self) -> Markup {
         match self.data.try_read() {
             Ok(data) => {
-                let title = data.title.clone().unwrap_or_else(|| "Untitled".to_string());
+                let title = data
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| self.locale.translate("page-untitled"));
                 let content = data.content.clone().unwrap_or_else(|| html! {});
                 let head = data.head.clone().unwrap_or_else(|| html! {});
                 let footer = data.footer.clone().unwrap_or_else(|| html! {});
+                let mut scripts = data.scripts.clone();
+                if !self.flashes.is_empty() {
+                    scripts.push(flash_js());
+                }
                 let current_tag = data.current_tag.clone();
 
-                let new_content = html! {
-                    (self.flash.flash_message_html())
+                let header = html! {
+                    (self.flashes.flash_message_html(&self.locale))
                     (self.build_navigation(current_tag))
+                };
+                let new_content = html! {
                     div .content-wrapper {
                         div .container .main-content {
                             (content)
@@ -142,8 +181,14 @@ impl ContextHtmlBuilder {
                 };
 
                 HtmlBuilder::new(title, new_content)
+                    .attach_locale(&self.locale)
+                    .attach_header(header)
+                    .attach_theme(self.theme)
+                    .attach_site_name(self.locale.translate("site-name"))
                     .attach_head(head)
                     .attach_footer(footer)
+                    .attach_scripts(scripts)
+                    .attach_nonce(self.nonce.as_ref().map(|n| n.0.to_string()))
                     .build()
             }
             Err(_) => {
@@ -153,55 +198,108 @@ impl ContextHtmlBuilder {
     }
 
     fn build_navigation(&self, tag: String) -> Markup {
-        let user_context = self.user_id_context.as_ref();
+        let user_context = self.user_id_context.as_deref();
         html! {
-            nav .nav-content {
+            nav .nav-content aria-label=(self.locale.translate("nav-label")) {
                 span .nav-home {
-                    a href="/" { "A Little Poem" }
+                    a href="/" { (self.locale.translate("site-name")) }
                 }
-                (self.parse_navigation(tag))
-                @if let Some(user_context) = user_context {
-                    span .nav-user {
-                        @if user_context.is_user {
-                            a href="/user/" { "Hello, " (user_context.username) }
-                        } @else {
-                            a href="/user/login/" { "You're a visitor, click here to login" }
-                        }
-                    }
-                } @else {
-                    span .nav-user {
-                        a .nav-user href="/user/login/" { "Login" }
+                (self.parse_navigation(NavigationSection::Main, &tag))
+                (self.build_language_switcher())
+                (self.build_theme_switcher())
+                span .nav-user {
+                    @for item in navigation_items(NavigationSection::Account, user_context) {
+                        (self.build_navigation_link(&item))
                     }
                 }
             }
         }
     }
 
-    fn parse_navigation(&self, tag: String) -> Markup {
-        let mut output = "".to_string();
-        for item in NavigationItem::navigations() {
-            let html = if item.tag == tag {
-                html! {
-                    span .nav-item .nav-item-active {
-                        a href=(item.url) { (item.name) }
-                    }
+    fn build_navigation_link(&self, item: &NavigationItem) -> Markup {
+        let username = self
+            .user_id_context
+            .as_ref()
+            .map(|user_context| user_context.username.clone())
+            .unwrap_or_default();
+        html! {
+            a href=(item.url) {
+                @if let Some(icon) = item.icon {
+                    span .nav-icon aria-hidden="true" { (icon()) }
                 }
-            } else {
-                html! {
-                    span .nav-item {
-                        a href=(item.url) { (item.name) }
-                    }
+                (self.locale.translate_with_args(&item.label, || {
+                    I18NArgs::default().set("username", username.clone())
+                }))
+            }
+        }
+    }
+
+    fn build_language_switcher(&self) -> Markup {
+        let languages =
+            std::iter::once((String::new(), self.locale.translate("nav-language-auto")))
+                .chain(
+                    available_languages()
+                        .iter()
+                        .map(|language| (language.id.clone(), language.name.clone())),
+                )
+                .collect();
+        self.form("nav-locale", self.locale.translate("nav-language-submit"))
+            .set_compact(true)
+            .set_action("/user/locale/")
+            .attach_hidden("return_to", &self.current_path)
+            .attach_field(
+                FormField::select("locale", self.locale.translate("nav-language"), languages)
+                    .set_value(self.language.as_deref().unwrap_or_default())
+                    .set_option_lang(true),
+            )
+            .build()
+    }
+
+    fn build_theme_switcher(&self) -> Markup {
+        let themes = Theme::ALL
+            .iter()
+            .map(|theme| {
+                (
+                    theme.as_str().to_string(),
+                    self.locale.translate(theme.label()),
+                )
+            })
+            .collect();
+        self.form("nav-theme", self.locale.translate("nav-theme-submit"))
+            .set_compact(true)
+            .set_action("/user/theme/")
+            .attach_hidden("return_to", &self.current_path)
+            .attach_field(
+                FormField::select("theme", self.locale.translate("nav-theme"), themes)
+                    .set_value(self.theme.as_str()),
+            )
+            .build()
+    }
+
+    fn parse_navigation(&self, section: NavigationSection, tag: &str) -> Markup {
+        let user_context = self.user_id_context.as_deref();
+        html! {
+            @for item in navigation_items(section, user_context) {
+                span .nav-item .nav-item-active[item.tag == tag] {
+                    (self.build_navigation_link(&item))
                 }
-            };
-            output.push_str(html.into_string().as_str());
+            }
         }
-        PreEscaped(output)
     }
 }
 
 impl FromContext for ContextHtmlBuilder {
     async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
-        Ok(Self::new(ctx.inject().await?, ctx.inject().await?))
+        let mut context_html_builder = Self::new(
+            ctx.inject().await?,
+            ctx.inject().await?,
+            CspNonce::from_request(ctx.req),
+        );
+        context_html_builder.language = cookie_language(ctx.req);
+        context_html_builder.csrf_token = ctx.req.extensions().get::<CsrfToken>().cloned();
+        context_html_builder.current_path = ctx.req.original_uri().path().to_string();
+        context_html_builder.theme = ctx.inject().await?;
+        Ok(context_html_builder)
     }
 }
 
@@ -209,6 +307,9 @@ impl FromUserContext for ContextHtmlBuilder {
     async fn from_user_context(ctx: &'_ UserContext<'_>) -> Result<Self, Report<ContextError>> {
         let mut context_html_builder: Self = ctx.inject_global().await?;
         context_html_builder.set_user_id_context(Arc::clone(&ctx.user_context));
+        context_html_builder.locale = ctx.inject().await?;
+        context_html_builder.language = user_language(ctx);
+        context_html_builder.time_zone = ctx.inject().await?;
         Ok(context_html_builder)
     }
 }
@@ -0,0 +1,184 @@
+use crate::common::csrf::CsrfTokenHtml;
+use crate::common::html::validate::ValidateErrorMessageExt;
+use maud::{Markup, html};
+use poem::web::CsrfToken;
+use std::sync::Arc;
+
+pub enum InputType {
+    Text,
+    Password,
+    /// Each option is a value and its label.
+    Select(Vec<(String, String)>),
+}
+
+pub struct FormField {
+    name: String,
+    label: String,
+    input_type: InputType,
+    value: String,
+    errors: Arc<[String]>,
+    autocomplete: Option<&'static str>,
+    option_lang: bool,
+}
+
+impl FormField {
+    pub fn new(input_type: InputType, name: &str, label: String) -> Self {
+        Self {
+            name: name.to_string(),
+            label,
+            input_type,
+            value: String::new(),
+            errors: Arc::new([]),
+            autocomplete: None,
+            option_lang: false,
+        }
+    }
+
+    pub fn text(name: &str, label: String) -> Self {
+        Self::new(InputType::Text, name, label)
+    }
+
+    pub fn password(name: &str, label: String) -> Self {
+        Self::new(InputType::Password, name, label)
+    }
+
+    pub fn select(name: &str, label: String, options: Vec<(String, String)>) -> Self {
+        Self::new(InputType::Select(options), name, label)
+    }
+
+    /// The submitted value, or the selected option. Never echoed for passwords.
+    pub fn set_value(mut self, value: &str) -> Self {
+        self.value = value.to_string();
+        self
+    }
+
+    pub fn attach_errors(mut self, errors: Arc<[String]>) -> Self {
+        self.errors = errors;
+        self
+    }
+
+    pub fn set_autocomplete(mut self, autocomplete: &'static str) -> Self {
+        self.autocomplete = Some(autocomplete);
+        self
+    }
+
+    /// For a list of languages, marks each option with its value as `lang`.
+    pub fn set_option_lang(mut self, option_lang: bool) -> Self {
+        self.option_lang = option_lang;
+        self
+    }
+
+    fn build(&self, form_id: &str) -> Markup {
+        let id = format!("{}-{}", form_id, self.name.replace('_', "-"));
+        let error_id = format!("{}-errors", id);
+        let invalid = !self.errors.is_empty();
+        let described_by = invalid.then_some(error_id.as_str());
+        let aria_invalid = invalid.then_some("true");
+        html! {
+            div .form-field {
+                label .form-label for=(id) { (self.label) }
+                @match &self.input_type {
+                    InputType::Select(options) => {
+                        select .form-item #(id) name=(self.name)
+                            aria-invalid=[aria_invalid] aria-describedby=[described_by] {
+                            @for (value, label) in options.iter() {
+                                @let lang = (self.option_lang && !value.is_empty()).then_some(value);
+                                option value=(value) lang=[lang] selected[*value == self.value] {
+                                    (label)
+                                }
+                            }
+                        }
+                    }
+                    InputType::Password => {
+                        input .form-item #(id) type="password" name=(self.name)
+                            autocomplete=[self.autocomplete]
+                            aria-invalid=[aria_invalid] aria-describedby=[described_by];
+                    }
+                    InputType::Text => {
+                        input .form-item #(id) type="text" name=(self.name)
+                            value=(self.value) autocomplete=[self.autocomplete]
+                            aria-invalid=[aria_invalid] aria-describedby=[described_by];
+                    }
+                }
+                (self.errors.clone().into_error_html_for(&error_id))
+            }
+        }
+    }
+}
+
+/// A `post` form with its CSRF token, labelled fields and a submit button.
+/// `ContextHtmlBuilder::form` starts one with the request's token attached.
+pub struct FormBuilder {
+    id: String,
+    action: Option<String>,
+    csrf_token: Option<CsrfToken>,
+    hidden: Vec<(String, String)>,
+    fields: Vec<FormField>,
+    submit_label: String,
+    compact: bool,
+}
+
+impl FormBuilder {
+    /// `id` also prefixes the id of every field.
+    pub fn new(id: &str, submit_label: String) -> Self {
+        Self {
+            id: id.to_string(),
+            action: None,
+            csrf_token: None,
+            hidden: Vec::new(),
+            fields: Vec::new(),
+            submit_label,
+            compact: false,
+        }
+    }
+
+    /// Posts to the current page when not set.
+    pub fn set_action(mut self, action: &str) -> Self {
+        self.action = Some(action.to_string());
+        self
+    }
+
+    pub fn attach_csrf_token(mut self, csrf_token: Option<CsrfToken>) -> Self {
+        self.csrf_token = csrf_token;
+        self
+    }
+
+    pub fn attach_hidden(mut self, name: &str, value: &str) -> Self {
+        self.hidden.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn attach_field(mut self, field: FormField) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// On one line with a plain button and the labels only read out,
+    /// for small forms such as the switchers in the navigation bar.
+    pub fn set_compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    pub fn build(&self) -> Markup {
+        let class = if self.compact { "form-compact" } else { "form" };
+        html! {
+            form class=(class) #(self.id) method="post" action=[self.action.as_deref()] {
+                @if let Some(csrf_token) = self.csrf_token.as_ref() {
+                    (csrf_token.as_html())
+                }
+                @for (name, value) in self.hidden.iter() {
+                    input type="hidden" name=(name) value=(value);
+                }
+                @for field in self.fields.iter() {
+                    (field.build(&self.id))
+                }
+                @if self.compact {
+                    button type="submit" { (self.submit_label) }
+                } @else {
+                    button .btn .btn-sky-blue .mt-3 type="submit" { (self.submit_label) }
+                }
+            }
+        }
+    }
+}
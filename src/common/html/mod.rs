@@ -1,17 +1,33 @@
+#[cfg(test)]
+mod audit;
 pub mod context_html;
+pub mod form;
+pub mod navigation;
+pub mod theme;
 pub mod validate;
 
 use crate::common::embed::{Asset, EmbedAsString};
+use crate::common::html::theme::Theme;
+use crate::common::locale::{FALLBACK_LANGUAGE, LocaleExtForDocument, LocaleExtForText};
 use maud::{DOCTYPE, Markup, PreEscaped, html};
+use poem::i18n::Locale;
 
-fn html_import_map() -> Markup {
+fn html_import_map(nonce: Option<&str>) -> Markup {
     let map = if cfg!(debug_assertions) {
         Asset::get("import_map/import_map.dev.min.json").as_string()
     } else {
         Asset::get("import_map/import_map.prod.min.json").as_string()
     };
     html! {
-        script type="importmap" { (PreEscaped(map)) }
+        script type="importmap" nonce=[nonce] { (PreEscaped(map)) }
+    }
+}
+
+fn html_module_scripts(scripts: &[String], nonce: Option<&str>) -> Markup {
+    html! {
+        @for script in scripts {
+            script type="module" nonce=[nonce] { (PreEscaped(script)) }
+        }
     }
 }
 
@@ -23,59 +39,120 @@ fn main_css_name() -> String {
     }
 }
 
-fn html_doc(title: &str, content: Markup, head: Markup, footer: Markup) -> Markup {
+fn html_doc(builder: HtmlBuilder) -> Markup {
+    let nonce = builder.nonce.as_deref();
     html! {
         (DOCTYPE)
-        html {
+        html lang=(builder.lang) dir=(builder.dir) class=[builder.theme.html_class()] {
             head {
                 meta charset="utf-8";
                 meta name="viewport" content="width=device-width, initial-scale=1";
-                title { (title) " | A Little Poem" }
+                title { (builder.title) " | " (builder.site_name) }
                 link rel="stylesheet" type="text/css" href=(main_css_name());
-                (html_import_map())
-                (head)
+                @if !builder.scripts.is_empty() {
+                    (html_import_map(nonce))
+                }
+                (builder.head.unwrap_or(html! {}))
             }
             body {
-                (content)
-                (footer)
+                a .skip-link href="#main-content" { (builder.skip_link_label) }
+                (builder.header.unwrap_or(html! {}))
+                main #main-content tabindex="-1" {
+                    (builder.content)
+                }
+                (builder.footer.unwrap_or(html! {}))
+                (html_module_scripts(&builder.scripts, nonce))
             }
         }
     }
 }
 
 pub struct HtmlBuilder {
+    lang: String,
+    dir: &'static str,
+    theme: Theme,
     title: String,
+    site_name: String,
+    skip_link_label: String,
     content: Markup,
+    header: Option<Markup>,
     head: Option<Markup>,
     footer: Option<Markup>,
+    scripts: Vec<String>,
+    nonce: Option<String>,
 }
 
 impl HtmlBuilder {
     pub fn new(title: String, content: Markup) -> Self {
         Self {
+            lang: FALLBACK_LANGUAGE.to_string(),
+            dir: "ltr",
+            theme: Theme::System,
             title,
+            site_name: "A Little Poem".to_string(),
+            skip_link_label: "Skip to content".to_string(),
             content,
+            header: None,
             head: None,
             footer: None,
+            scripts: Vec::new(),
+            nonce: None,
         }
     }
 
+    /// Sets `lang` and `dir` on `<html>`, pages without one are in [`FALLBACK_LANGUAGE`].
+    pub fn attach_locale(mut self, locale: &Locale) -> Self {
+        self.lang = locale.language_id();
+        self.dir = locale.direction();
+        self.skip_link_label = locale.translate("skip-to-content");
+        self
+    }
+
+    /// Rendered as a class on `<html>`, so the first paint already has the right colours.
+    pub fn attach_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Shown after the title, translated by `ContextHtmlBuilder`.
+    pub fn attach_site_name(mut self, site_name: String) -> Self {
+        self.site_name = site_name;
+        self
+    }
+
     pub fn attach_head(mut self, head: Markup) -> Self {
         self.head = Some(head);
         self
     }
 
+    /// Rendered between the skip link and `<main>`, such as the navigation.
+    pub fn attach_header(mut self, header: Markup) -> Self {
+        self.header = Some(header);
+        self
+    }
+
     pub fn attach_footer(mut self, footer: Markup) -> Self {
         self.footer = Some(footer);
         self
     }
 
+    /// Inline ES module, rendered at the end of `body` with the CSP nonce.
+    pub fn attach_script(mut self, script: String) -> Self {
+        self.scripts.push(script);
+        self
+    }
+
+    pub fn attach_scripts(mut self, scripts: Vec<String>) -> Self {
+        self.scripts.extend(scripts);
+        self
+    }
+
+    pub fn attach_nonce(mut self, nonce: Option<String>) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
     pub fn build(self) -> Markup {
-        html_doc(
-            &self.title,
-            self.content,
-            self.head.unwrap_or(html! {}),
-            self.footer.unwrap_or(html! {}),
-        )
+        html_doc(self)
     }
 }
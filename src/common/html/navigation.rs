@@ -0,0 +1,108 @@
+use crate::user::model::UserIdContext;
+use maud::Markup;
+use std::sync::{Arc, LazyLock, RwLock};
+
+static NAVIGATION: LazyLock<RwLock<Vec<Arc<NavigationItem>>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// Who sees an entry, pages built without a user context count as visitors.
+pub enum NavigationVisibility {
+    Everyone,
+    VisitorOnly,
+    UserOnly,
+    /// For checks beyond logged in or not, such as a role.
+    When(fn(&UserIdContext) -> bool),
+}
+
+impl NavigationVisibility {
+    pub fn is_visible(&self, user_id_context: Option<&UserIdContext>) -> bool {
+        match self {
+            Self::Everyone => true,
+            Self::VisitorOnly => user_id_context.is_none_or(|user| !user.is_user),
+            Self::UserOnly => user_id_context.is_some_and(|user| user.is_user),
+            Self::When(predicate) => user_id_context.is_some_and(predicate),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+pub enum NavigationSection {
+    /// The links after the site name.
+    Main,
+    /// The account links at the end of the bar.
+    Account,
+}
+
+pub struct NavigationItem {
+    /// FTL key of the label, given `$username`.
+    pub label: String,
+    pub url: String,
+    /// Matched against `ContextHtmlBuilder::set_current_tag` to mark the active page.
+    pub tag: String,
+    pub order: i32,
+    pub icon: Option<fn() -> Markup>,
+    pub visibility: NavigationVisibility,
+    pub section: NavigationSection,
+}
+
+impl NavigationItem {
+    pub fn new(label: &str, url: &str, tag: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            url: url.to_string(),
+            tag: tag.to_string(),
+            order: 0,
+            icon: None,
+            visibility: NavigationVisibility::Everyone,
+            section: NavigationSection::Main,
+        }
+    }
+
+    pub fn set_order(mut self, order: i32) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn attach_icon(mut self, icon: fn() -> Markup) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn set_visibility(mut self, visibility: NavigationVisibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    pub fn set_section(mut self, section: NavigationSection) -> Self {
+        self.section = section;
+        self
+    }
+}
+
+/// Called by each module as its route is built. An entry with the same label
+/// replaces the old one, so building a route twice does not duplicate it.
+pub fn register_navigation(item: NavigationItem) {
+    if let Ok(mut navigation) = NAVIGATION.write() {
+        navigation.retain(|existing| existing.label != item.label);
+        navigation.push(Arc::new(item));
+        navigation.sort_by_key(|item| item.order);
+    }
+}
+
+/// Entries of `section` that `user_id_context` may see, in order.
+pub fn navigation_items(
+    section: NavigationSection,
+    user_id_context: Option<&UserIdContext>,
+) -> Vec<Arc<NavigationItem>> {
+    NAVIGATION
+        .read()
+        .map(|navigation| {
+            navigation
+                .iter()
+                .filter(|item| item.section == section)
+                .filter(|item| item.visibility.is_visible(user_id_context))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
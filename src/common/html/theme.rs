@@ -0,0 +1,64 @@
+use crate::common::context::{Context, ContextError, FromContext};
+use error_stack::Report;
+
+/// Set by the theme switcher, read before the first render so the page never flashes.
+pub const THEME_COOKIE: &str = "theme";
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Theme {
+    /// Follows `prefers-color-scheme`.
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Self; 3] = [Self::System, Self::Light, Self::Dark];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "system" => Some(Self::System),
+            "light" => Some(Self::Light),
+            "dark" => Some(Self::Dark),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+
+    /// FTL key of the switcher option.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::System => "nav-theme-system",
+            Self::Light => "nav-theme-light",
+            Self::Dark => "nav-theme-dark",
+        }
+    }
+
+    /// Class on `<html>`, `None` leaves it to the media query.
+    pub fn html_class(&self) -> Option<&'static str> {
+        match self {
+            Self::System => None,
+            Self::Light => Some("light"),
+            Self::Dark => Some("dark"),
+        }
+    }
+}
+
+impl FromContext for Theme {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(ctx
+            .req
+            .cookie()
+            .get(THEME_COOKIE)
+            .and_then(|cookie| Self::parse(cookie.value_str()))
+            .unwrap_or_default())
+    }
+}
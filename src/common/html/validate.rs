@@ -1,12 +1,12 @@
 use maud::{Markup, html};
 use std::sync::Arc;
 
-fn arc_string_to_html_error(messages: Arc<[String]>) -> Markup {
+fn arc_string_to_html_error(messages: Arc<[String]>, id: Option<&str>) -> Markup {
     if messages.is_empty() {
         return html! {};
     }
     html! {
-        ul .validation-error-list {
+        ul .validation-error-list id=[id] {
             @for message in messages.iter() {
                 li .validation-error-message { (message) }
             }
@@ -16,16 +16,27 @@ fn arc_string_to_html_error(messages: Arc<[String]>) -> Markup {
 
 pub trait ValidateErrorMessageExt {
     fn into_error_html(self) -> Markup;
+
+    /// With `id` on the list, for the field's `aria-describedby`.
+    fn into_error_html_for(self, id: &str) -> Markup;
 }
 
 impl ValidateErrorMessageExt for Arc<[String]> {
     fn into_error_html(self) -> Markup {
-        arc_string_to_html_error(self)
+        arc_string_to_html_error(self, None)
+    }
+
+    fn into_error_html_for(self, id: &str) -> Markup {
+        arc_string_to_html_error(self, Some(id))
     }
 }
 
 impl ValidateErrorMessageExt for Vec<String> {
     fn into_error_html(self) -> Markup {
-        arc_string_to_html_error(self.into())
+        arc_string_to_html_error(self.into(), None)
+    }
+
+    fn into_error_html_for(self, id: &str) -> Markup {
+        arc_string_to_html_error(self.into(), Some(id))
     }
 }
@@ -4,3 +4,19 @@ use maud::{Markup, PreEscaped};
 pub fn plus_icon() -> Markup {
     PreEscaped(Asset::get("icon/plus.svg").as_string())
 }
+
+pub fn home_icon() -> Markup {
+    PreEscaped(Asset::get("icon/home.svg").as_string())
+}
+
+pub fn list_icon() -> Markup {
+    PreEscaped(Asset::get("icon/list.svg").as_string())
+}
+
+pub fn user_icon() -> Markup {
+    PreEscaped(Asset::get("icon/user.svg").as_string())
+}
+
+pub fn login_icon() -> Markup {
+    PreEscaped(Asset::get("icon/login.svg").as_string())
+}
@@ -0,0 +1,95 @@
+use crate::common::config::poem::ListenerConfig;
+use crate::common::error::{ExtraResultExt, FromIntoStackError};
+use crate::common::tls::rustls_config_stream;
+use error_stack::{Report, ResultExt};
+use poem::listener::{BoxListener, Listener, TcpListener};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ListenerError {
+    #[error("No listeners configured")]
+    NoListeners,
+    #[error("TLS error")]
+    Tls,
+    #[error("Unix socket error")]
+    UnixSocket,
+}
+
+impl FromIntoStackError for ListenerError {}
+
+#[cfg(unix)]
+fn unix_listener(path: &str, mode: Option<u32>) -> Result<BoxListener, Report<ListenerError>> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    // A socket left behind by an unclean exit would make the bind fail.
+    if let Ok(metadata) = std::fs::symlink_metadata(path)
+        && metadata.file_type().is_socket()
+    {
+        std::fs::remove_file(path)
+            .change_context(ListenerError::UnixSocket)
+            .attach_critical_lazy(|| format!("Unable to remove stale socket '{}'", path))?;
+    }
+
+    let listener = poem::listener::UnixListener::bind(path.to_string());
+    Ok(match mode {
+        Some(mode) => listener
+            .with_permissions(std::fs::Permissions::from_mode(mode))
+            .boxed(),
+        None => listener.boxed(),
+    })
+}
+
+#[cfg(not(unix))]
+fn unix_listener(path: &str, _mode: Option<u32>) -> Result<BoxListener, Report<ListenerError>> {
+    Err(ListenerError::UnixSocket.into_stack_error_critical(format!(
+        "Unix sockets are not supported on this platform: '{}'",
+        path
+    )))
+}
+
+fn build_single_listener(config: &ListenerConfig) -> Result<BoxListener, Report<ListenerError>> {
+    Ok(match config {
+        ListenerConfig::Tcp { address, port } => {
+            TcpListener::bind(format!("{}:{}", address, port)).boxed()
+        }
+        ListenerConfig::Unix { path, mode } => unix_listener(path, *mode)?,
+        ListenerConfig::Tls {
+            address,
+            port,
+            cert_path,
+            key_path,
+            ..
+        } => TcpListener::bind(format!("{}:{}", address, port))
+            .rustls(rustls_config_stream(cert_path, key_path).change_context(ListenerError::Tls)?)
+            .boxed(),
+    })
+}
+
+/// Combines every configured listener into one, so a single server accepts on all of them.
+pub fn build_listener(configs: &[ListenerConfig]) -> Result<BoxListener, Report<ListenerError>> {
+    let mut combined: Option<BoxListener> = None;
+    for config in configs {
+        let listener = build_single_listener(config)?;
+        combined = Some(match combined {
+            None => listener,
+            Some(combined) => combined.combine(listener).boxed(),
+        });
+    }
+    combined.ok_or_else(|| ListenerError::NoListeners.into_stack_error())
+}
+
+/// `(redirect address, https port)` for each TLS listener with a redirect port.
+pub fn redirect_addresses(configs: &[ListenerConfig]) -> Vec<(String, u16)> {
+    configs
+        .iter()
+        .filter_map(|config| match config {
+            ListenerConfig::Tls {
+                address,
+                port,
+                redirect_port: Some(redirect_port),
+                ..
+            } => Some((format!("{}:{}", address, redirect_port), *port)),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,286 @@
+use crate::common::embed::EmbedAsString;
+use crate::common::locale::format::{DATE_PATTERN_KEYS, is_date_pattern};
+use crate::common::locale::{FALLBACK_LANGUAGE, LocaleAsset};
+use fluent_syntax::ast::{Entry, Expression, InlineExpression, Pattern, PatternElement};
+use fluent_syntax::parser;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+pub enum TranslationProblem {
+    Syntax {
+        locale: String,
+        message: String,
+    },
+    MissingKey {
+        locale: String,
+        key: String,
+    },
+    ExtraKey {
+        locale: String,
+        key: String,
+    },
+    MismatchedVariables {
+        locale: String,
+        key: String,
+        expected: BTreeSet<String>,
+        found: BTreeSet<String>,
+    },
+    InvalidDatePattern {
+        locale: String,
+        key: String,
+        pattern: String,
+    },
+}
+
+fn format_variables(variables: &BTreeSet<String>) -> String {
+    if variables.is_empty() {
+        return "none".to_string();
+    }
+    variables
+        .iter()
+        .map(|variable| format!("${}", variable))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Display for TranslationProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax { locale, message } => write!(f, "{}: {}", locale, message),
+            Self::MissingKey { locale, key } => write!(f, "{}: missing '{}'", locale, key),
+            Self::ExtraKey { locale, key } => {
+                write!(f, "{}: '{}' is not in {}", locale, key, FALLBACK_LANGUAGE)
+            }
+            Self::MismatchedVariables {
+                locale,
+                key,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{}: '{}' uses {}, expected {}",
+                locale,
+                key,
+                format_variables(found),
+                format_variables(expected)
+            ),
+            Self::InvalidDatePattern {
+                locale,
+                key,
+                pattern,
+            } => write!(
+                f,
+                "{}: '{}' is not a valid date pattern: '{}'",
+                locale, key, pattern
+            ),
+        }
+    }
+}
+
+fn inline_variables(expression: &InlineExpression<&str>, out: &mut BTreeSet<String>) {
+    match expression {
+        InlineExpression::VariableReference { id } => {
+            out.insert(id.name.to_string());
+        }
+        InlineExpression::FunctionReference { arguments, .. } => {
+            for argument in arguments.positional.iter() {
+                inline_variables(argument, out);
+            }
+            for argument in arguments.named.iter() {
+                inline_variables(&argument.value, out);
+            }
+        }
+        InlineExpression::Placeable { expression } => expression_variables(expression, out),
+        _ => {}
+    }
+}
+
+fn expression_variables(expression: &Expression<&str>, out: &mut BTreeSet<String>) {
+    match expression {
+        Expression::Inline(expression) => inline_variables(expression, out),
+        Expression::Select { selector, variants } => {
+            inline_variables(selector, out);
+            for variant in variants.iter() {
+                pattern_variables(&variant.value, out);
+            }
+        }
+    }
+}
+
+fn pattern_variables(pattern: &Pattern<&str>, out: &mut BTreeSet<String>) {
+    for element in pattern.elements.iter() {
+        if let PatternElement::Placeable { expression } = element {
+            expression_variables(expression, out);
+        }
+    }
+}
+
+/// The pattern must be plain text that chrono can parse, it is formatted without arguments.
+fn check_date_pattern(
+    locale: &str,
+    key: &str,
+    value: &Pattern<&str>,
+    problems: &mut Vec<TranslationProblem>,
+) {
+    let mut pattern = String::new();
+    let mut plain = true;
+    for element in value.elements.iter() {
+        match element {
+            PatternElement::TextElement { value } => pattern.push_str(value),
+            PatternElement::Placeable { .. } => plain = false,
+        }
+    }
+    if !plain || !is_date_pattern(&pattern) {
+        problems.push(TranslationProblem::InvalidDatePattern {
+            locale: locale.to_string(),
+            key: key.to_string(),
+            pattern,
+        });
+    }
+}
+
+/// Adds the keys of messages, terms (with their leading `-`) and attributes
+/// (`key.attribute`) in one file, each with the variables it uses.
+fn parse_keys(
+    locale: &str,
+    file: &str,
+    content: &str,
+    keys: &mut BTreeMap<String, BTreeSet<String>>,
+    problems: &mut Vec<TranslationProblem>,
+) {
+    let resource = match parser::parse(content) {
+        Ok(resource) => resource,
+        Err((resource, errors)) => {
+            for error in errors {
+                let line = content[..error.pos.start.min(content.len())]
+                    .matches('\n')
+                    .count()
+                    + 1;
+                problems.push(TranslationProblem::Syntax {
+                    locale: locale.to_string(),
+                    message: format!("{}:{}: {}", file, line, error),
+                });
+            }
+            resource
+        }
+    };
+
+    for entry in resource.body.iter() {
+        let (key, value, attributes) = match entry {
+            Entry::Message(message) => (
+                message.id.name.to_string(),
+                message.value.as_ref(),
+                &message.attributes,
+            ),
+            Entry::Term(term) => (
+                format!("-{}", term.id.name),
+                Some(&term.value),
+                &term.attributes,
+            ),
+            _ => continue,
+        };
+        if let Some(value) = value {
+            let mut variables = BTreeSet::new();
+            pattern_variables(value, &mut variables);
+            keys.insert(key.clone(), variables);
+            if DATE_PATTERN_KEYS.contains(&key.as_str()) {
+                check_date_pattern(locale, &key, value, problems);
+            }
+        }
+        for attribute in attributes.iter() {
+            let mut variables = BTreeSet::new();
+            pattern_variables(&attribute.value, &mut variables);
+            keys.insert(format!("{}.{}", key, attribute.id.name), variables);
+        }
+    }
+}
+
+fn locale_keys(
+    problems: &mut Vec<TranslationProblem>,
+) -> BTreeMap<String, BTreeMap<String, BTreeSet<String>>> {
+    let mut locales: BTreeMap<String, BTreeMap<String, BTreeSet<String>>> = BTreeMap::new();
+    for path in LocaleAsset::iter() {
+        let Some((locale, file)) = path.split_once("/") else {
+            continue;
+        };
+        let content = LocaleAsset::get(path.as_ref()).as_string();
+        let keys = locales.entry(locale.to_string()).or_default();
+        parse_keys(locale, file, &content, keys, problems);
+    }
+    locales
+}
+
+/// Compares every locale under `asset/locale/` with [`FALLBACK_LANGUAGE`].
+pub fn check_translations() -> Vec<TranslationProblem> {
+    let mut problems = Vec::new();
+    let locales = locale_keys(&mut problems);
+    let Some(reference) = locales.get(FALLBACK_LANGUAGE) else {
+        problems.push(TranslationProblem::Syntax {
+            locale: FALLBACK_LANGUAGE.to_string(),
+            message: "reference locale is missing".to_string(),
+        });
+        return problems;
+    };
+
+    for (locale, keys) in locales.iter() {
+        if locale == FALLBACK_LANGUAGE {
+            continue;
+        }
+        for (key, expected) in reference.iter() {
+            match keys.get(key) {
+                None => problems.push(TranslationProblem::MissingKey {
+                    locale: locale.clone(),
+                    key: key.clone(),
+                }),
+                Some(found) if found != expected => {
+                    problems.push(TranslationProblem::MismatchedVariables {
+                        locale: locale.clone(),
+                        key: key.clone(),
+                        expected: expected.clone(),
+                        found: found.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for key in keys.keys().filter(|key| !reference.contains_key(*key)) {
+            problems.push(TranslationProblem::ExtraKey {
+                locale: locale.clone(),
+                key: key.clone(),
+            });
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_locales_match_the_reference() {
+        let problems: Vec<String> = check_translations()
+            .iter()
+            .map(|problem| problem.to_string())
+            .collect();
+        assert!(problems.is_empty(), "{}", problems.join("\n"));
+    }
+
+    #[test]
+    fn date_patterns_must_parse() {
+        let mut keys = BTreeMap::new();
+        let mut problems = Vec::new();
+        parse_keys(
+            "xx",
+            "language.ftl",
+            "format-date = %d/%m/%Y\nformat-datetime = %d/%m/%Y %",
+            &mut keys,
+            &mut problems,
+        );
+        let problems: Vec<String> = problems.iter().map(|p| p.to_string()).collect();
+        assert_eq!(
+            problems,
+            ["xx: 'format-datetime' is not a valid date pattern: '%d/%m/%Y %'"]
+        );
+    }
+}
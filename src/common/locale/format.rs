@@ -0,0 +1,150 @@
+use crate::common::context::ContextError;
+use crate::common::context::user::{FromUserContext, UserContext};
+use crate::common::locale::LocaleExtForText;
+use chrono::format::StrftimeItems;
+use chrono::{DateTime, TimeDelta, Utc};
+use chrono_tz::Tz;
+use error_stack::Report;
+use poem::i18n::{I18NArgs, Locale};
+
+/// Logged-in users pick their own, everyone else sees UTC.
+impl FromUserContext for Tz {
+    async fn from_user_context(ctx: &'_ UserContext<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(ctx
+            .user_context
+            .time_zone
+            .as_deref()
+            .and_then(|time_zone| time_zone.parse().ok())
+            .unwrap_or(Tz::UTC))
+    }
+}
+
+/// Keys holding strftime patterns, checked by `check-translations`.
+pub const DATE_PATTERN_KEYS: [&str; 2] = ["format-date", "format-datetime"];
+
+/// Used instead of a translated pattern that is not valid strftime.
+const ISO_DATE: &str = "%Y-%m-%d";
+const ISO_DATETIME: &str = "%Y-%m-%d %H:%M";
+
+pub fn is_date_pattern(pattern: &str) -> bool {
+    StrftimeItems::new(pattern).parse().is_ok()
+}
+
+/// Formatting an invalid pattern directly panics, so it is parsed first.
+fn format_time(time: &DateTime<Utc>, time_zone: Tz, pattern: &str, fallback: &str) -> String {
+    let items = StrftimeItems::new(pattern)
+        .parse()
+        .or_else(|_| StrftimeItems::new(fallback).parse())
+        .unwrap_or_default();
+    time.with_timezone(&time_zone)
+        .format_with_items(items.iter())
+        .to_string()
+}
+
+fn group_digits(digits: &str, separator: &str) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 * separator.len());
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Date patterns and separators come from `language.ftl`, so every language
+/// can differ even where the rest of its text is shared.
+pub trait LocaleExtForFormat {
+    fn format_date(&self, time: &DateTime<Utc>, time_zone: Tz) -> String;
+
+    fn format_datetime(&self, time: &DateTime<Utc>, time_zone: Tz) -> String;
+
+    fn format_integer(&self, value: i64) -> String;
+
+    fn format_decimal(&self, value: f64, decimals: usize) -> String;
+
+    /// "3 days ago" or "in 2 hours", relative to `now`.
+    fn format_relative(&self, time: &DateTime<Utc>, now: &DateTime<Utc>) -> String;
+}
+
+impl LocaleExtForFormat for Locale {
+    fn format_date(&self, time: &DateTime<Utc>, time_zone: Tz) -> String {
+        format_time(time, time_zone, &self.translate("format-date"), ISO_DATE)
+    }
+
+    fn format_datetime(&self, time: &DateTime<Utc>, time_zone: Tz) -> String {
+        format_time(
+            time,
+            time_zone,
+            &self.translate("format-datetime"),
+            ISO_DATETIME,
+        )
+    }
+
+    fn format_integer(&self, value: i64) -> String {
+        let digits = group_digits(
+            &value.unsigned_abs().to_string(),
+            &self.translate("format-number-group"),
+        );
+        if value < 0 {
+            format!("-{}", digits)
+        } else {
+            digits
+        }
+    }
+
+    fn format_decimal(&self, value: f64, decimals: usize) -> String {
+        let formatted = format!("{:.*}", decimals, value.abs());
+        let (integer, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+        let mut output = group_digits(integer, &self.translate("format-number-group"));
+        if !fraction.is_empty() {
+            output.push_str(&self.translate("format-number-decimal"));
+            output.push_str(fraction);
+        }
+        // Rounding to zero drops the sign, as in "-0.00".
+        if value < 0.0 && formatted.bytes().any(|b| matches!(b, b'1'..=b'9')) {
+            output.insert(0, '-');
+        }
+        output
+    }
+
+    fn format_relative(&self, time: &DateTime<Utc>, now: &DateTime<Utc>) -> String {
+        let delta = *now - *time;
+        let past = delta >= TimeDelta::zero();
+        let seconds = delta.num_seconds().unsigned_abs();
+        let (unit, count) = match seconds {
+            0..45 => return self.translate("relative-time-now"),
+            45..2_700 => ("minutes", (seconds / 60).max(1)),
+            2_700..79_200 => ("hours", (seconds / 3_600).max(1)),
+            79_200..2_246_400 => ("days", (seconds / 86_400).max(1)),
+            2_246_400..27_648_000 => ("months", (seconds / 2_592_000).max(1)),
+            _ => ("years", (seconds / 31_536_000).max(1)),
+        };
+        let id = if past {
+            format!("relative-time-{}-ago", unit)
+        } else {
+            format!("relative-time-in-{}", unit)
+        };
+        self.translate_with_args(&id, || I18NArgs::default().set("count", count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn invalid_patterns_fall_back_to_iso() {
+        let time = Utc.with_ymd_and_hms(2026, 3, 9, 14, 5, 0).unwrap();
+        assert!(!is_date_pattern("%d/%m/%Y %"));
+        assert_eq!(
+            format_time(&time, Tz::UTC, "%d/%m/%Y %", ISO_DATE),
+            "2026-03-09"
+        );
+        assert_eq!(
+            format_time(&time, Tz::Europe__Paris, "%d/%m/%Y %H:%M", ISO_DATETIME),
+            "09/03/2026 15:05"
+        );
+    }
+}
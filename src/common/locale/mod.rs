@@ -1,20 +1,52 @@
+use crate::common::context::user::{FromUserContext, UserContext};
 use crate::common::context::{Context, ContextError, FromContext};
 use crate::common::embed::EmbedAsString;
+use crate::common::locale::check::check_translations;
 use cjtoolkit_structured_validator::common::locale::{LocaleData, LocaleValue, ValidateErrorStore};
 use cjtoolkit_structured_validator::common::validation_collector::AsValidateErrorStore;
 use error_stack::{Report, ResultExt};
-use poem::FromRequest;
 use poem::error::I18NError;
-use poem::i18n::{I18NArgs, I18NResources, Locale};
+use poem::http::header::ACCEPT_LANGUAGE;
+use poem::i18n::unic_langid::{CharacterDirection, LanguageIdentifier};
+use poem::i18n::{I18NArgs, I18NBundle, I18NResources, Locale, NegotiationStrategy};
+use poem::{FromRequest, Request};
 use rust_embed::Embed;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+
+pub mod check;
+pub mod format;
+
+/// Every key must exist here, other languages may be incomplete.
+pub const FALLBACK_LANGUAGE: &str = "en-GB";
+
+/// Set by the language switcher, takes priority over `Accept-Language`.
+pub const LOCALE_COOKIE: &str = "locale";
+
+static FALLBACK_BUNDLE: OnceLock<I18NBundle> = OnceLock::new();
+static LANGUAGES: OnceLock<Box<[Language]>> = OnceLock::new();
+static WARNED_KEYS: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
 
 #[derive(Embed)]
 #[folder = "$CARGO_MANIFEST_DIR/asset/locale/"]
 struct LocaleAsset;
 
+pub struct Language {
+    pub id: String,
+    /// `language-name` from the language's own FTL files.
+    pub name: String,
+}
+
 impl LocaleAsset {
+    fn language_ids() -> Vec<String> {
+        let mut ids: Vec<String> = Self::iter()
+            .filter_map(|value| value.split_once("/").map(|(id, _)| id.to_string()))
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
     fn locale_map() -> HashMap<String, String> {
         let mut map = HashMap::new();
         for value in Self::iter() {
@@ -32,20 +64,203 @@ impl LocaleAsset {
     }
 }
 
+fn fallback_language() -> LanguageIdentifier {
+    FALLBACK_LANGUAGE.parse().unwrap_or_default()
+}
+
+/// Negotiates a single language per request, so a key missing from it is noticed
+/// and looked up in [`FALLBACK_LANGUAGE`] instead.
 pub fn build_resources() -> Result<I18NResources, I18NError> {
     let locale_map = LocaleAsset::locale_map();
-    let mut resources = I18NResources::builder();
+    let mut resources = I18NResources::builder()
+        .default_language(fallback_language())
+        .negotiation_strategy(NegotiationStrategy::Lookup);
     for (locale, content) in locale_map {
         resources = resources.add_ftl(locale, content);
     }
-    resources.build()
+    let resources = resources.build()?;
+    for problem in check_translations() {
+        eprintln!("Translation check: {}", problem);
+    }
+    let _ = FALLBACK_BUNDLE.set(resources.negotiate_languages(&[fallback_language()]));
+    let _ = LANGUAGES.set(
+        LocaleAsset::language_ids()
+            .into_iter()
+            .map(|id| {
+                let name = id
+                    .parse::<LanguageIdentifier>()
+                    .ok()
+                    .and_then(|language| {
+                        resources
+                            .negotiate_languages(&[language])
+                            .text("language-name")
+                            .ok()
+                    })
+                    .unwrap_or_else(|| id.clone());
+                Language { id, name }
+            })
+            .collect(),
+    );
+    Ok(resources)
+}
+
+/// One per directory under `asset/locale/`, sorted by id.
+pub fn available_languages() -> &'static [Language] {
+    LANGUAGES
+        .get()
+        .map(|languages| languages.as_ref())
+        .unwrap_or_default()
+}
+
+pub fn is_available_language(id: &str) -> bool {
+    available_languages()
+        .iter()
+        .any(|language| language.id == id)
+}
+
+/// The language cookie, if it names a language that still exists.
+pub fn cookie_language(req: &Request) -> Option<String> {
+    req.cookie()
+        .get(LOCALE_COOKIE)
+        .map(|cookie| cookie.value_str().to_string())
+        .filter(|id| is_available_language(id))
+}
+
+/// Negotiates as if `preferred` came first in `Accept-Language`, so the header still
+/// decides when `preferred` is `None`.
+async fn negotiate_locale(
+    req: &Request,
+    preferred: Option<&str>,
+) -> Result<Locale, Report<ContextError>> {
+    let Some(preferred) = preferred else {
+        return Locale::from_request_without_body(req)
+            .await
+            .change_context(ContextError::RequestError);
+    };
+    let accept_language = match req.header(ACCEPT_LANGUAGE) {
+        Some(header) => format!("{}, {}", preferred, header),
+        None => preferred.to_string(),
+    };
+    let mut preferred_req = Request::builder()
+        .header(ACCEPT_LANGUAGE, accept_language)
+        .finish();
+    if let Some(resources) = req.extensions().get::<I18NResources>() {
+        preferred_req.extensions_mut().insert(resources.clone());
+    }
+    Locale::from_request_without_body(&preferred_req)
+        .await
+        .change_context(ContextError::RequestError)
+}
+
+fn warn_missing_once(id: &str, found_in_fallback: bool) {
+    let warned = WARNED_KEYS.get_or_init(|| Mutex::new(HashSet::new()));
+    if let Ok(mut warned) = warned.lock()
+        && warned.insert(id.to_string())
+    {
+        if found_in_fallback {
+            eprintln!(
+                "Translation '{}' is missing, using {}",
+                id, FALLBACK_LANGUAGE
+            );
+        } else {
+            eprintln!(
+                "Translation '{}' is missing, including from {}",
+                id, FALLBACK_LANGUAGE
+            );
+        }
+    }
+}
+
+pub trait LocaleExtForText {
+    /// Text for `id`, from [`FALLBACK_LANGUAGE`] with a warning when the request
+    /// language lacks it, or `id` itself when both do.
+    fn translate(&self, id: &str) -> String {
+        self.translate_with_args(id, I18NArgs::default)
+    }
+
+    /// `args` is called again for the fallback lookup, as `I18NArgs` is not `Clone`.
+    fn translate_with_args<'a>(&self, id: &str, args: impl Fn() -> I18NArgs<'a>) -> String {
+        self.translate_or(id, args, || id.to_string())
+    }
+
+    fn translate_or<'a>(
+        &self,
+        id: &str,
+        args: impl Fn() -> I18NArgs<'a>,
+        original: impl FnOnce() -> String,
+    ) -> String;
+}
+
+impl LocaleExtForText for Locale {
+    fn translate_or<'a>(
+        &self,
+        id: &str,
+        args: impl Fn() -> I18NArgs<'a>,
+        original: impl FnOnce() -> String,
+    ) -> String {
+        if let Ok(text) = self.text_with_args(id, args()) {
+            return text;
+        }
+        match FALLBACK_BUNDLE
+            .get()
+            .and_then(|bundle| bundle.text_with_args(id, args()).ok())
+        {
+            Some(text) => {
+                warn_missing_once(id, true);
+                text
+            }
+            None => {
+                warn_missing_once(id, false);
+                original()
+            }
+        }
+    }
+}
+
+/// `lang` and `dir` of the `<html>` element.
+pub trait LocaleExtForDocument {
+    /// `language-id` from the language's own FTL files.
+    fn language_id(&self) -> String;
+
+    /// `rtl` for scripts such as Arabic or Hebrew, otherwise `ltr`.
+    fn direction(&self) -> &'static str;
+}
+
+impl LocaleExtForDocument for Locale {
+    fn language_id(&self) -> String {
+        self.translate("language-id")
+    }
+
+    fn direction(&self) -> &'static str {
+        match self
+            .language_id()
+            .parse::<LanguageIdentifier>()
+            .map(|language| language.character_direction())
+        {
+            Ok(CharacterDirection::RTL) => "rtl",
+            _ => "ltr",
+        }
+    }
 }
 
 impl FromContext for Locale {
     async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
-        Locale::from_request_without_body(ctx.req)
-            .await
-            .change_context(ContextError::RequestError)
+        negotiate_locale(ctx.req, cookie_language(ctx.req).as_deref()).await
+    }
+}
+
+/// A logged-in user's saved language comes before the cookie.
+pub fn user_language(ctx: &UserContext<'_>) -> Option<String> {
+    ctx.user_context
+        .locale
+        .clone()
+        .filter(|id| is_available_language(id))
+        .or_else(|| cookie_language(ctx.req))
+}
+
+impl FromUserContext for Locale {
+    async fn from_user_context(ctx: &'_ UserContext<'_>) -> Result<Self, Report<ContextError>> {
+        negotiate_locale(ctx.req, user_language(ctx).as_deref()).await
     }
 }
 
@@ -55,7 +270,7 @@ pub trait LocaleExtForData {
 
 impl LocaleExtForData for LocaleData {
     fn get_translation(&self, locale: &Locale, original: String) -> String {
-        if !self.args.is_empty() {
+        let args = || {
             let mut values = I18NArgs::default();
             for (key, value) in self.args.iter() {
                 match value {
@@ -73,12 +288,9 @@ impl LocaleExtForData for LocaleData {
                     }
                 }
             }
-            locale
-                .text_with_args(self.name.clone(), values)
-                .unwrap_or(original)
-        } else {
-            locale.text(self.name.clone()).unwrap_or(original)
-        }
+            values
+        };
+        locale.translate_or(&self.name, args, || original)
     }
 }
 
@@ -0,0 +1,249 @@
+use crate::common::config::Config;
+use crate::common::context::Dep;
+use crate::common::security::has_bearer_token;
+use crate::user::repository::UserRepository;
+use poem::http::StatusCode;
+use poem::{Endpoint, IntoResponse, PathPattern, Request, Response, Route, get, handler};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+pub const METRICS_PATH: &str = "/metrics";
+
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+const LOCK_WAIT_BUCKETS: [f64; 8] = [0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5];
+
+struct Histogram {
+    bounds: &'static [f64],
+    buckets: Box<[u64]>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            buckets: vec![0; bounds.len()].into(),
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(self.bounds) {
+            if value <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn write(&self, out: &mut String, name: &str, labels: &str) {
+        let sep = if labels.is_empty() { "" } else { "," };
+        for (bucket, bound) in self.buckets.iter().zip(self.bounds) {
+            let _ = writeln!(out, "{name}_bucket{{{labels}{sep}le=\"{bound}\"}} {bucket}");
+        }
+        let _ = writeln!(
+            out,
+            "{name}_bucket{{{labels}{sep}le=\"+Inf\"}} {}",
+            self.count
+        );
+        let labels = if labels.is_empty() {
+            "".to_string()
+        } else {
+            format!("{{{labels}}}")
+        };
+        let _ = writeln!(out, "{name}_sum{labels} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{labels} {}", self.count);
+    }
+}
+
+pub struct Metrics {
+    http_requests: Mutex<BTreeMap<(String, String), u64>>,
+    http_statuses: Mutex<BTreeMap<&'static str, u64>>,
+    http_latency: Mutex<BTreeMap<String, Histogram>>,
+    sqlite_lock_wait: Mutex<Histogram>,
+    login_success: AtomicU64,
+    login_failure: AtomicU64,
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(|| Metrics {
+    http_requests: Mutex::new(BTreeMap::new()),
+    http_statuses: Mutex::new(BTreeMap::new()),
+    http_latency: Mutex::new(BTreeMap::new()),
+    sqlite_lock_wait: Mutex::new(Histogram::new(&LOCK_WAIT_BUCKETS)),
+    login_success: AtomicU64::new(0),
+    login_failure: AtomicU64::new(0),
+});
+
+fn status_class(status: StatusCode) -> &'static str {
+    match status.as_u16() {
+        100..=199 => "1xx",
+        200..=299 => "2xx",
+        300..=399 => "3xx",
+        400..=499 => "4xx",
+        _ => "5xx",
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl Metrics {
+    pub fn get() -> &'static Self {
+        &METRICS
+    }
+
+    pub fn record_request(&self, method: &str, route: &str, status: StatusCode, elapsed: Duration) {
+        if let Ok(mut requests) = self.http_requests.lock() {
+            *requests
+                .entry((method.to_string(), route.to_string()))
+                .or_default() += 1;
+        }
+        if let Ok(mut statuses) = self.http_statuses.lock() {
+            *statuses.entry(status_class(status)).or_default() += 1;
+        }
+        if let Ok(mut latency) = self.http_latency.lock() {
+            latency
+                .entry(route.to_string())
+                .or_insert_with(|| Histogram::new(&LATENCY_BUCKETS))
+                .observe(elapsed.as_secs_f64());
+        }
+    }
+
+    pub fn record_sqlite_lock_wait(&self, elapsed: Duration) {
+        if let Ok(mut histogram) = self.sqlite_lock_wait.lock() {
+            histogram.observe(elapsed.as_secs_f64());
+        }
+    }
+
+    pub fn record_login(&self, success: bool) {
+        if success {
+            self.login_success.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.login_failure.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn render(&self, active_tokens: Option<i64>) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP http_requests_total Total HTTP requests by method and route.\n");
+        out.push_str("# TYPE http_requests_total counter\n");
+        if let Ok(requests) = self.http_requests.lock() {
+            for ((method, route), count) in requests.iter() {
+                let _ = writeln!(
+                    out,
+                    "http_requests_total{{method=\"{}\",route=\"{}\"}} {count}",
+                    escape_label(method),
+                    escape_label(route)
+                );
+            }
+        }
+
+        out.push_str("# HELP http_responses_total Total HTTP responses by status class.\n");
+        out.push_str("# TYPE http_responses_total counter\n");
+        if let Ok(statuses) = self.http_statuses.lock() {
+            for (class, count) in statuses.iter() {
+                let _ = writeln!(out, "http_responses_total{{status=\"{class}\"}} {count}");
+            }
+        }
+
+        out.push_str("# HELP http_request_duration_seconds HTTP request latency by route.\n");
+        out.push_str("# TYPE http_request_duration_seconds histogram\n");
+        if let Ok(latency) = self.http_latency.lock() {
+            for (route, histogram) in latency.iter() {
+                histogram.write(
+                    &mut out,
+                    "http_request_duration_seconds",
+                    format!("route=\"{}\"", escape_label(route)).as_str(),
+                );
+            }
+        }
+
+        out.push_str(
+            "# HELP sqlite_lock_wait_seconds Time spent waiting for the SQLite connection lock.\n",
+        );
+        out.push_str("# TYPE sqlite_lock_wait_seconds histogram\n");
+        if let Ok(histogram) = self.sqlite_lock_wait.lock() {
+            histogram.write(&mut out, "sqlite_lock_wait_seconds", "");
+        }
+
+        out.push_str("# HELP user_logins_total User login attempts by result.\n");
+        out.push_str("# TYPE user_logins_total counter\n");
+        let _ = writeln!(
+            out,
+            "user_logins_total{{result=\"success\"}} {}",
+            self.login_success.load(Ordering::Relaxed)
+        );
+        let _ = writeln!(
+            out,
+            "user_logins_total{{result=\"failure\"}} {}",
+            self.login_failure.load(Ordering::Relaxed)
+        );
+
+        if let Some(active_tokens) = active_tokens {
+            out.push_str("# HELP user_active_tokens Unexpired user login tokens.\n");
+            out.push_str("# TYPE user_active_tokens gauge\n");
+            let _ = writeln!(out, "user_active_tokens {active_tokens}");
+        }
+
+        out
+    }
+}
+
+pub async fn metrics_middleware<E: Endpoint>(next: E, req: Request) -> poem::Result<Response> {
+    let method = req.method().to_string();
+    let start = Instant::now();
+    let result = next.call(req).await.map(IntoResponse::into_response);
+    let elapsed = start.elapsed();
+
+    let (status, pattern) = match &result {
+        Ok(res) => (res.status(), res.data::<PathPattern>()),
+        Err(err) => (err.status(), err.data::<PathPattern>()),
+    };
+    let route = pattern.map(|p| p.0.as_ref()).unwrap_or("unmatched");
+    Metrics::get().record_request(method.as_str(), route, status, elapsed);
+
+    result
+}
+
+/// Without a token only the separate metrics listener serves this route.
+fn is_authorized(req: &Request, bearer_token: Option<&str>) -> bool {
+    match bearer_token {
+        None => true,
+        Some(token) => has_bearer_token(req, token),
+    }
+}
+
+#[handler]
+async fn metrics(req: &Request, Dep(user_repository): Dep<Box<dyn UserRepository>>) -> Response {
+    let config = Config::fetch().await.ok();
+    let bearer_token = config
+        .as_ref()
+        .and_then(|c| c.metrics.bearer_token.as_deref());
+    if !is_authorized(req, bearer_token) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let active_tokens = user_repository.count_active_tokens().await.ok();
+    Metrics::get()
+        .render(active_tokens)
+        .with_content_type("text/plain; version=0.0.4")
+        .into_response()
+}
+
+pub fn route_metrics() -> Route {
+    Route::new().at("/", get(metrics))
+}
@@ -1,4 +1,5 @@
 pub mod adapter;
+pub mod backup;
 pub mod cache_local;
 pub mod config;
 pub mod context;
@@ -8,7 +9,14 @@ pub mod db;
 pub mod embed;
 pub mod error;
 pub mod flash;
+pub mod health;
 pub mod html;
 pub mod icon;
+pub mod listener;
 pub mod locale;
+pub mod metrics;
 pub mod password;
+pub mod proxy;
+pub mod security;
+pub mod shutdown;
+pub mod tls;
@@ -0,0 +1,235 @@
+use crate::common::config::proxy::ProxyConfig;
+use crate::common::error::FromIntoStackError;
+use error_stack::Report;
+use ipnet::IpNet;
+use poem::http::uri::Scheme;
+use poem::{Endpoint, FromRequest, Middleware, Request, RequestBody};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[error("Invalid trusted proxy CIDR")]
+pub struct ProxyConfigError;
+
+impl FromIntoStackError for ProxyConfigError {}
+
+/// The client as seen before any trusted proxies, falls back to the direct peer.
+#[derive(Debug, Clone)]
+pub struct ClientInfo {
+    pub ip: Option<IpAddr>,
+    pub scheme: Scheme,
+}
+
+impl ClientInfo {
+    pub fn is_secure(&self) -> bool {
+        self.scheme == Scheme::HTTPS
+    }
+
+    fn from_peer(req: &Request) -> Self {
+        Self {
+            ip: req.remote_addr().as_socket_addr().map(SocketAddr::ip),
+            scheme: req.scheme().clone(),
+        }
+    }
+}
+
+impl<'a> FromRequest<'a> for ClientInfo {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> poem::Result<Self> {
+        Ok(req
+            .extensions()
+            .get::<ClientInfo>()
+            .cloned()
+            .unwrap_or_else(|| Self::from_peer(req)))
+    }
+}
+
+struct TrustedNetworks {
+    networks: Box<[IpNet]>,
+    trust_unix_socket: bool,
+}
+
+impl TrustedNetworks {
+    fn contains(&self, ip: &IpAddr) -> bool {
+        self.networks.iter().any(|net| net.contains(ip))
+    }
+
+    fn is_trusted_peer(&self, req: &Request) -> bool {
+        match req.remote_addr().as_socket_addr() {
+            Some(addr) => self.contains(&addr.ip()),
+            None => self.trust_unix_socket && req.remote_addr().as_unix_socket_addr().is_some(),
+        }
+    }
+}
+
+/// One hop of the forwarding chain, ordered client first.
+struct Hop {
+    ip: Option<IpAddr>,
+    proto: Option<String>,
+}
+
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(ip);
+    }
+    if let Ok(addr) = node.parse::<SocketAddr>() {
+        return Some(addr.ip());
+    }
+    // `[2001:db8::1]` without a port.
+    node.strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .and_then(|v| v.parse().ok())
+}
+
+fn parse_forwarded(values: &[&str]) -> Vec<Hop> {
+    let mut hops = Vec::new();
+    for element in values.iter().flat_map(|v| v.split(',')) {
+        let mut hop = Hop {
+            ip: None,
+            proto: None,
+        };
+        for pair in element.split(';') {
+            let Some((key, value)) = pair.split_once('=') else {
+                continue;
+            };
+            match key.trim().to_ascii_lowercase().as_str() {
+                "for" => hop.ip = parse_node(value),
+                "proto" => hop.proto = Some(value.trim().trim_matches('"').to_ascii_lowercase()),
+                _ => {}
+            }
+        }
+        hops.push(hop);
+    }
+    hops
+}
+
+fn parse_x_forwarded(for_values: &[&str], proto_values: &[&str]) -> Vec<Hop> {
+    let ips: Vec<Option<IpAddr>> = for_values
+        .iter()
+        .flat_map(|v| v.split(','))
+        .map(parse_node)
+        .collect();
+    let protos: Vec<String> = proto_values
+        .iter()
+        .flat_map(|v| v.split(','))
+        .map(|v| v.trim().to_ascii_lowercase())
+        .collect();
+
+    ips.iter()
+        .enumerate()
+        .map(|(i, ip)| Hop {
+            ip: *ip,
+            // Proxies that only append to X-Forwarded-For leave a shorter proto list.
+            proto: protos
+                .get(i)
+                .or_else(|| {
+                    if protos.len() == 1 {
+                        protos.first()
+                    } else {
+                        None
+                    }
+                })
+                .cloned(),
+        })
+        .collect()
+}
+
+fn header_values<'a>(req: &'a Request, name: &str) -> Vec<&'a str> {
+    req.headers()
+        .get_all(name)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .collect()
+}
+
+fn resolve_client(req: &Request, trusted: &TrustedNetworks) -> ClientInfo {
+    let peer = ClientInfo::from_peer(req);
+    if !trusted.is_trusted_peer(req) {
+        return peer;
+    }
+
+    let forwarded = header_values(req, "forwarded");
+    let hops = if !forwarded.is_empty() {
+        parse_forwarded(&forwarded)
+    } else {
+        parse_x_forwarded(
+            &header_values(req, "x-forwarded-for"),
+            &header_values(req, "x-forwarded-proto"),
+        )
+    };
+
+    // Walk back from the nearest hop, skipping our own proxies, the first
+    // untrusted address is the client.
+    let mut client = None;
+    for hop in hops.iter().rev() {
+        client = Some(hop);
+        match hop.ip {
+            Some(ip) if trusted.contains(&ip) => continue,
+            _ => break,
+        }
+    }
+
+    match client {
+        None => peer,
+        Some(hop) => ClientInfo {
+            ip: hop.ip,
+            scheme: match hop.proto.as_deref() {
+                Some("https") => Scheme::HTTPS,
+                Some("http") => Scheme::HTTP,
+                _ => peer.scheme,
+            },
+        },
+    }
+}
+
+pub struct TrustedProxy(Arc<TrustedNetworks>);
+
+impl TrustedProxy {
+    pub fn new(config: &ProxyConfig) -> Result<Self, Report<ProxyConfigError>> {
+        let mut networks = Vec::new();
+        for cidr in config.trusted.iter() {
+            let net = match cidr.parse::<IpNet>() {
+                Ok(net) => net,
+                Err(_) => match cidr.parse::<IpAddr>() {
+                    Ok(ip) => IpNet::from(ip),
+                    Err(_) => {
+                        return Err(ProxyConfigError
+                            .into_stack_error_critical(format!("'{}' is not a valid CIDR", cidr)));
+                    }
+                },
+            };
+            networks.push(net);
+        }
+        Ok(Self(Arc::new(TrustedNetworks {
+            networks: networks.into(),
+            trust_unix_socket: config.trust_unix_socket,
+        })))
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for TrustedProxy {
+    type Output = TrustedProxyEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        TrustedProxyEndpoint {
+            inner: ep,
+            trusted: Arc::clone(&self.0),
+        }
+    }
+}
+
+pub struct TrustedProxyEndpoint<E> {
+    inner: E,
+    trusted: Arc<TrustedNetworks>,
+}
+
+impl<E: Endpoint> Endpoint for TrustedProxyEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> poem::Result<Self::Output> {
+        let client = resolve_client(&req, &self.trusted);
+        req.extensions_mut().insert(client);
+        self.inner.call(req).await
+    }
+}
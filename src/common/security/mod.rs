@@ -0,0 +1,97 @@
+use crate::common::config::Config;
+use crate::common::config::security::SecurityHeadersConfig;
+use crate::common::proxy::ClientInfo;
+use poem::http::HeaderValue;
+use poem::http::header::{
+    AUTHORIZATION, CONTENT_SECURITY_POLICY, REFERRER_POLICY, STRICT_TRANSPORT_SECURITY,
+    X_CONTENT_TYPE_OPTIONS,
+};
+use poem::http::uri::Scheme;
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response};
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+/// Per-request nonce that every inline `script` tag must carry.
+#[derive(Debug, Clone)]
+pub struct CspNonce(pub Arc<str>);
+
+impl CspNonce {
+    fn generate() -> Self {
+        Self(Uuid::new_v4().simple().to_string().into())
+    }
+
+    pub fn from_request(req: &Request) -> Option<Self> {
+        req.extensions().get::<Self>().cloned()
+    }
+}
+
+/// Whether the request carries `Authorization: Bearer <token>`, compared in constant time
+/// so the response time does not give the token away.
+pub fn has_bearer_token(req: &Request, token: &str) -> bool {
+    req.header(AUTHORIZATION)
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|v| bool::from(v.as_bytes().ct_eq(token.as_bytes())))
+}
+
+/// Reads the header policy from the current config on every request, so a reload applies immediately.
+pub struct SecurityHeaders;
+
+impl<E: Endpoint> Middleware<E> for SecurityHeaders {
+    type Output = SecurityHeadersEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        SecurityHeadersEndpoint { inner: ep }
+    }
+}
+
+pub struct SecurityHeadersEndpoint<E> {
+    inner: E,
+}
+
+fn set_header(res: &mut Response, name: poem::http::HeaderName, value: &str) {
+    if let Ok(value) = HeaderValue::from_str(value) {
+        res.headers_mut().insert(name, value);
+    }
+}
+
+impl<E: Endpoint> Endpoint for SecurityHeadersEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> poem::Result<Self::Output> {
+        let nonce = CspNonce::generate();
+        let is_secure = match req.extensions().get::<ClientInfo>() {
+            Some(client) => client.is_secure(),
+            None => *req.scheme() == Scheme::HTTPS,
+        };
+        req.extensions_mut().insert(nonce.clone());
+
+        let config = match Config::fetch().await {
+            Ok(config) => Arc::clone(&config.security_headers),
+            Err(_) => Arc::new(SecurityHeadersConfig::default()),
+        };
+
+        let mut res = match self.inner.call(req).await {
+            Ok(res) => res.into_response(),
+            Err(err) => err.into_response(),
+        };
+
+        set_header(
+            &mut res,
+            CONTENT_SECURITY_POLICY,
+            &config.content_security_policy.replace("{nonce}", &nonce.0),
+        );
+        set_header(&mut res, X_CONTENT_TYPE_OPTIONS, "nosniff");
+        set_header(&mut res, REFERRER_POLICY, &config.referrer_policy);
+        set_header(
+            &mut res,
+            poem::http::HeaderName::from_static("permissions-policy"),
+            &config.permissions_policy,
+        );
+        if is_secure && let Some(hsts) = config.strict_transport_security.as_ref() {
+            set_header(&mut res, STRICT_TRANSPORT_SECURITY, hsts);
+        }
+
+        Ok(res)
+    }
+}
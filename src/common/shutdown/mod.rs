@@ -0,0 +1,72 @@
+use std::sync::LazyLock;
+use tokio::sync::watch;
+
+static SHUTDOWN: LazyLock<watch::Sender<bool>> = LazyLock::new(|| watch::channel(false).0);
+
+pub struct Shutdown;
+
+impl Shutdown {
+    pub fn trigger() {
+        SHUTDOWN.send_replace(true);
+    }
+
+    /// Resolves once shutdown has been triggered, suitable for handing to background tasks.
+    pub async fn wait() {
+        let mut rx = SHUTDOWN.subscribe();
+        let _ = rx.wait_for(|triggered| *triggered).await;
+    }
+}
+
+#[cfg(unix)]
+pub type HangupSignal = Option<tokio::signal::unix::Signal>;
+
+#[cfg(unix)]
+pub fn hangup_signal() -> HangupSignal {
+    use tokio::signal::unix::{SignalKind, signal};
+    signal(SignalKind::hangup()).ok()
+}
+
+#[cfg(unix)]
+pub async fn wait_for_hangup(hangup: &mut HangupSignal) -> Option<()> {
+    hangup.as_mut()?.recv().await
+}
+
+#[cfg(not(unix))]
+pub type HangupSignal = ();
+
+#[cfg(not(unix))]
+pub fn hangup_signal() -> HangupSignal {}
+
+#[cfg(not(unix))]
+pub async fn wait_for_hangup(_hangup: &mut HangupSignal) -> Option<()> {
+    None
+}
+
+#[cfg(unix)]
+async fn terminate_signal() {
+    use tokio::signal::unix::{SignalKind, signal};
+    match signal(SignalKind::terminate()) {
+        Ok(mut sigterm) => {
+            sigterm.recv().await;
+        }
+        Err(_) => std::future::pending().await,
+    }
+}
+
+#[cfg(not(unix))]
+async fn terminate_signal() {
+    std::future::pending().await
+}
+
+/// Triggers `Shutdown` on SIGTERM or Ctrl-C.
+pub fn listen_for_shutdown_signal() {
+    tokio::spawn(async {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate_signal() => {}
+            _ = Shutdown::wait() => return,
+        }
+        println!("Shutdown signal received, draining connections");
+        Shutdown::trigger();
+    });
+}
@@ -0,0 +1,85 @@
+use crate::common::error::{ExtraResultExt, FromIntoStackError};
+use crate::common::shutdown::{hangup_signal, wait_for_hangup};
+use error_stack::{Report, ResultExt};
+use futures_util::stream::{self, BoxStream, StreamExt};
+use poem::http::StatusCode;
+use poem::http::header::HOST;
+use poem::listener::RustlsConfig;
+use poem::web::Redirect;
+use poem::{IntoResponse, Request, Response, handler};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TlsError {
+    #[error("Certificate read error")]
+    CertificateRead,
+    #[error("Key read error")]
+    KeyRead,
+}
+
+impl FromIntoStackError for TlsError {}
+
+fn load_rustls_config(cert_path: &str, key_path: &str) -> Result<RustlsConfig, Report<TlsError>> {
+    let cert = std::fs::read(cert_path)
+        .change_context(TlsError::CertificateRead)
+        .attach_critical_lazy(|| format!("Unable to read certificate '{}'", cert_path))?;
+    let key = std::fs::read(key_path)
+        .change_context(TlsError::KeyRead)
+        .attach_critical_lazy(|| format!("Unable to read key '{}'", key_path))?;
+    Ok(RustlsConfig::new().fallback(poem::listener::RustlsCertificate::new().cert(cert).key(key)))
+}
+
+/// Loads the certificate once, then again every time the process receives SIGHUP.
+/// A failed reload keeps serving the previous certificate.
+pub fn rustls_config_stream(
+    cert_path: &str,
+    key_path: &str,
+) -> Result<BoxStream<'static, RustlsConfig>, Report<TlsError>> {
+    let cert_path = cert_path.to_string();
+    let key_path = key_path.to_string();
+    let initial = load_rustls_config(&cert_path, &key_path)?;
+
+    let reloads = stream::unfold(hangup_signal(), move |mut hangup| {
+        let cert_path = cert_path.clone();
+        let key_path = key_path.clone();
+        async move {
+            loop {
+                wait_for_hangup(&mut hangup).await?;
+                match load_rustls_config(&cert_path, &key_path) {
+                    Ok(config) => {
+                        println!("TLS certificate reloaded");
+                        return Some((config, hangup));
+                    }
+                    Err(err) => eprintln!("TLS certificate reload failed: {:?}", err),
+                }
+            }
+        }
+    });
+
+    Ok(stream::once(async { initial }).chain(reloads).boxed())
+}
+
+fn strip_port(host: &str) -> &str {
+    match host.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => host,
+    }
+}
+
+#[handler]
+pub async fn redirect_to_https(req: &Request, https_port: poem::web::Data<&u16>) -> Response {
+    let host = match req.header(HOST) {
+        Some(host) => strip_port(host),
+        None => return StatusCode::BAD_REQUEST.into_response(),
+    };
+    let path = req
+        .uri()
+        .path_and_query()
+        .map(|p| p.as_str())
+        .unwrap_or("/");
+    let location = match **https_port {
+        443 => format!("https://{}{}", host, path),
+        port => format!("https://{}:{}{}", host, port, path),
+    };
+    Redirect::permanent(location).into_response()
+}
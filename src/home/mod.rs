@@ -1,49 +1,55 @@
 use crate::common::context::user::JustDep;
 use crate::common::embed::{Asset, AssetFileEndpoint, EmbedAsString};
 use crate::common::html::context_html::ContextHtmlBuilder;
-use crate::common::icon::plus_icon;
-use maud::{Markup, PreEscaped, html};
+use crate::common::html::navigation::{NavigationItem, register_navigation};
+use crate::common::icon::{home_icon, plus_icon};
+use crate::common::locale::LocaleExtForText;
+use maud::{Markup, html};
+use poem::i18n::I18NArgs;
 use poem::web::Json;
 use poem::{Route, get, handler};
 use serde_json::{Value, json};
 
 #[handler]
 pub async fn home_page(JustDep(context_html_builder, _): JustDep<ContextHtmlBuilder>) -> Markup {
-    let title = "Rust Vue Exercise";
+    let locale = &context_html_builder.locale;
+    let exercise = |number: usize| {
+        locale.translate_with_args("home-exercise", || {
+            I18NArgs::default().set("number", number)
+        })
+    };
+    let title = locale.translate("home-title");
     context_html_builder
-        .attach_title(title)
+        .attach_title(title.as_str())
         .set_current_tag("home")
         .attach_content(html! {
             h1 .mt-3 { (title) }
-            p .mt-3 { "This is Rust Vue Exercise." }
-            h2 .mt-3 { "Exercise 1" }
+            p .mt-3 { (locale.translate("home-intro")) }
+            h2 .mt-3 { (exercise(1)) }
             div #app .mt-3 v-cloak { "{{ message }}" }
-            h2 .mt-3 { "Exercise 2" }
+            h2 .mt-3 { (exercise(2)) }
             div #counter .mt-3 v-cloak {
                 button .btn .btn-sky-blue "@click"="count++" {
-                    "Count is: {{ count }}  "
+                    (locale.translate("home-count")) " {{ count }}  "
                     (plus_icon())
                 }
             }
-            h2 .mt-3 { "Exercise 3" }
+            h2 .mt-3 { (exercise(3)) }
             div #array .mt-3 v-cloak {
                 ul .ul-bullet {
                     li "v-for"="(item) in items" { "{{ item }}" }
                 }
             }
         })
-        .attach_footer(root_js())
+        .attach_script(root_js())
         .build()
 }
 
-fn root_js() -> Markup {
-    let js = if cfg!(debug_assertions) {
+fn root_js() -> String {
+    if cfg!(debug_assertions) {
         Asset::get("js/root.js").as_string()
     } else {
         Asset::get("js/root.min.js").as_string()
-    };
-    html! {
-        script type="module" { (PreEscaped(js)) }
     }
 }
 
@@ -53,6 +59,12 @@ pub async fn js_array() -> Json<Value> {
 }
 
 pub fn route_home_page() -> Route {
+    register_navigation(
+        NavigationItem::new("nav-home", "/", "home")
+            .set_order(100)
+            .attach_icon(home_icon),
+    );
+
     Route::new()
         .at("/", get(home_page))
         .at("/array", get(js_array))
@@ -1,21 +1,35 @@
 use crate::bucket_list::route::{BUCKET_LIST_PATH, route_bucket_list};
+use crate::cli::{Cli, Command};
+use crate::common::backup::{BACKUP_PATH, backup_now, schedule_backups};
 use crate::common::cache_local::init_cache_local;
 use crate::common::config::Config;
+use crate::common::config::reload::listen_for_reload_signal;
 use crate::common::csrf::{CSRF_PATH, route_csrf};
+use crate::common::db::SqliteClient;
 use crate::common::embed::{AssetFilesEndpoint, EMBED_PATH};
+use crate::common::flash::header::flash_header_middleware;
+use crate::common::health::{HEALTHZ_PATH, READYZ_PATH, healthz, readyz};
+use crate::common::listener::{build_listener, redirect_addresses};
 use crate::common::locale::build_resources;
+use crate::common::metrics::{METRICS_PATH, metrics_middleware, route_metrics};
+use crate::common::proxy::TrustedProxy;
+use crate::common::security::SecurityHeaders;
+use crate::common::shutdown::{Shutdown, listen_for_shutdown_signal};
+use crate::common::tls::redirect_to_https;
 use crate::home::route_home_page;
 use crate::user::model::UserIdContext;
 use crate::user::route::{USER_PATH, route_user};
+use clap::Parser;
 use error_stack::{Report, ResultExt};
 use poem::listener::TcpListener;
 use poem::middleware::{CookieJarManager, Csrf};
 use poem::session::{CookieConfig, CookieSession};
-use poem::{EndpointExt, Server};
+use poem::{Endpoint, EndpointExt, Route, Server, get, post};
 use std::sync::Arc;
 use thiserror::Error;
 
 pub mod bucket_list;
+pub mod cli;
 pub mod common;
 pub mod home;
 pub mod user;
@@ -28,37 +42,113 @@ pub enum MainError {
     IoError,
     #[error("Locale error")]
     LocaleError,
+    #[error("Database error")]
+    DatabaseError,
+    #[error("Listener error")]
+    ListenerError,
+    #[error("Command error")]
+    CommandError,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Report<MainError>> {
-    let config = Config::fetch()
-        .await
-        .change_context(MainError::ConfigError)?;
-
-    let route = route_home_page();
+    match Cli::parse().command {
+        None | Some(Command::Serve) => serve().await,
+        Some(command) => cli::run(command)
+            .await
+            .change_context(MainError::CommandError),
+    }
+}
 
+/// The pages and middleware on top of `route`, shared with the accessibility tests.
+/// `config` is shared as request data for middleware that only needs restart-only settings.
+pub fn build_app(
+    route: Route,
+    config: &Arc<Config>,
+) -> Result<impl Endpoint + use<>, Report<MainError>> {
     let route = route
         .nest(BUCKET_LIST_PATH, route_bucket_list())
         .nest(USER_PATH, route_user())
         .nest(CSRF_PATH, route_csrf())
         .nest(EMBED_PATH, AssetFilesEndpoint::new());
 
-    let route = route
+    Ok(route
         .with(CookieJarManager::new())
+        .around(flash_header_middleware)
         .with(CookieSession::new(CookieConfig::new()))
         .with(Csrf::new())
         .data(build_resources().change_context(MainError::LocaleError)?)
-        .around(init_cache_local::<Arc<UserIdContext>, _>);
+        .data(Arc::clone(config))
+        .around(init_cache_local::<Arc<UserIdContext>, _>)
+        .with(SecurityHeaders)
+        .with(TrustedProxy::new(&config.proxy).change_context(MainError::ConfigError)?)
+        .around(metrics_middleware))
+}
+
+async fn serve() -> Result<(), Report<MainError>> {
+    let config = Config::fetch()
+        .await
+        .change_context(MainError::ConfigError)?;
+
+    let route = route_home_page()
+        .at(HEALTHZ_PATH, get(healthz))
+        .at(READYZ_PATH, get(readyz))
+        .at(BACKUP_PATH, post(backup_now));
+
+    listen_for_shutdown_signal();
+    listen_for_reload_signal();
+    schedule_backups();
+    let shutdown_timeout = Some(config.poem.shutdown_timeout());
+    let mut background_tasks = Vec::new();
 
-    match config.upgrade() {
-        Some(config) => {
-            println!("Listening on http://{}", config.poem.parse_address());
-            Server::new(TcpListener::bind(config.poem.parse_address().as_str()))
-                .run(route)
-                .await
-                .change_context(MainError::IoError)
+    let route = match config.metrics.address.as_ref() {
+        Some(metrics_address) => {
+            println!(
+                "Metrics listening on http://{}{}",
+                metrics_address, METRICS_PATH
+            );
+            let metrics_server = Server::new(TcpListener::bind(metrics_address.clone()))
+                .run_with_graceful_shutdown(
+                    poem::Route::new().nest(METRICS_PATH, route_metrics()),
+                    Shutdown::wait(),
+                    shutdown_timeout,
+                );
+            background_tasks.push(tokio::spawn(metrics_server));
+            route
         }
-        None => Err(Report::new(MainError::ConfigError)),
+        None if config.metrics.bearer_token.is_some() => route.nest(METRICS_PATH, route_metrics()),
+        None => route,
+    };
+
+    let route = build_app(route, &config)?;
+
+    for (redirect_address, https_port) in redirect_addresses(&config.poem.listeners) {
+        println!("Redirecting http://{} to HTTPS", redirect_address);
+        let redirect_server = Server::new(TcpListener::bind(redirect_address))
+            .run_with_graceful_shutdown(
+                redirect_to_https.data(https_port),
+                Shutdown::wait(),
+                shutdown_timeout,
+            );
+        background_tasks.push(tokio::spawn(redirect_server));
     }
+
+    let listener =
+        build_listener(&config.poem.listeners).change_context(MainError::ListenerError)?;
+    for listener_config in config.poem.listeners.iter() {
+        println!("Listening on {}", listener_config.describe());
+    }
+    let result = Server::new(listener)
+        .run_with_graceful_shutdown(route, Shutdown::wait(), shutdown_timeout)
+        .await
+        .change_context(MainError::IoError);
+
+    Shutdown::trigger();
+    for task in background_tasks {
+        let _ = task.await;
+    }
+    SqliteClient::close_cached().change_context(MainError::DatabaseError)?;
+    println!("Shutdown complete");
+
+    result
 }
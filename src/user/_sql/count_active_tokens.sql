@@ -0,0 +1,3 @@
+SELECT COUNT(*) AS active
+FROM user_login_tokens
+WHERE expire_after > datetime('now');
\ No newline at end of file
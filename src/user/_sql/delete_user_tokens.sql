@@ -0,0 +1,3 @@
+DELETE
+FROM user_login_tokens
+WHERE user_id = :user_id;
\ No newline at end of file
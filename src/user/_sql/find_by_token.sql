@@ -1,4 +1,4 @@
-SELECT u.id, u.username
+SELECT u.id, u.username, u.locale, u.time_zone
 FROM users AS u
          INNER JOIN user_login_tokens ult on u.id = ult.user_id
 WHERE ult.token = :token
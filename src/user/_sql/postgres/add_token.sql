@@ -0,0 +1,2 @@
+INSERT INTO user_login_tokens(user_id, token, expire_after)
+VALUES ($1, $2, now() + interval '30 days')
\ No newline at end of file
@@ -0,0 +1,3 @@
+DELETE
+from user_login_tokens
+WHERE token = $1;
\ No newline at end of file
@@ -0,0 +1,6 @@
+SELECT u.id, u.username, u.locale, u.time_zone
+FROM users AS u
+         INNER JOIN user_login_tokens ult on u.id = ult.user_id
+WHERE ult.token = $1
+  AND ult.expire_after > now()
+LIMIT 1;
\ No newline at end of file
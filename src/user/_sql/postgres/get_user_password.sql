@@ -0,0 +1,4 @@
+SELECT id, password
+FROM users
+WHERE username = $1
+LIMIT 1;
\ No newline at end of file
@@ -0,0 +1,8 @@
+SELECT u.id,
+       u.username,
+       (SELECT COUNT(*)
+        FROM user_login_tokens AS ult
+        WHERE ult.user_id = u.id
+          AND ult.expire_after > now()) AS active_tokens
+FROM users AS u
+ORDER BY u.id;
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO users(username, password)
+VALUES ($1, $2)
\ No newline at end of file
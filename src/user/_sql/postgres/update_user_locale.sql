@@ -0,0 +1,3 @@
+UPDATE users
+SET locale = $2
+WHERE id = $1;
@@ -0,0 +1,4 @@
+UPDATE users
+SET password = $2
+WHERE username = $1
+RETURNING id;
\ No newline at end of file
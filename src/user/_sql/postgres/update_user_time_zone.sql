@@ -0,0 +1,3 @@
+UPDATE users
+SET time_zone = $2
+WHERE id = $1;
@@ -0,0 +1 @@
+SELECT EXISTS(SELECT 1 FROM users WHERE username = $1) AS taken
\ No newline at end of file
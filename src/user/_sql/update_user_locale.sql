@@ -0,0 +1,3 @@
+UPDATE users
+SET locale = :locale
+WHERE id = :user_id;
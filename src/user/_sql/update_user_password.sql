@@ -0,0 +1,4 @@
+UPDATE users
+SET password = :password
+WHERE username = :username
+RETURNING id;
\ No newline at end of file
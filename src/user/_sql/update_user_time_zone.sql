@@ -0,0 +1,3 @@
+UPDATE users
+SET time_zone = :time_zone
+WHERE id = :user_id;
@@ -1,14 +1,18 @@
 use crate::common::html::context_html::ContextHtmlBuilder;
-use crate::common::html::validate::ValidateErrorMessageExt;
+use crate::common::html::form::FormField;
+use crate::common::html::theme::Theme;
+use crate::common::locale::{LocaleExtForText, is_available_language};
 use crate::user::model::{
     UserLoginFormValidated, UserLoginFormValidationError, UserLoginFormValidationErrorMessage,
     UserRegisterFormValidated,
 };
 use crate::user::rules::{PasswordRulesExt, UsernameRulesExt};
+use chrono_tz::Tz;
 use cjtoolkit_structured_validator::common::flag_error::FlagCounter;
-use cjtoolkit_structured_validator::types::password::Password;
+use cjtoolkit_structured_validator::types::password::{Password, PasswordError};
 use cjtoolkit_structured_validator::types::username::{IsUsernameTakenAsync, Username};
 use maud::{Markup, html};
+use poem::http::Uri;
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Clone, Serialize, Deserialize)]
@@ -66,30 +70,46 @@ impl UserRegisterForm {
         UserRegisterFormResult::new(self.clone(), service).await
     }
 
+    /// The register password rules, for passwords set outside this form.
+    pub fn validate_password(password: &str) -> Result<Password, PasswordError> {
+        Password::parse_user_register(Some(password), password).0
+    }
+
     pub fn html_form(
         title: String,
         context_html_builder: &ContextHtmlBuilder,
         user_register_form: Option<UserRegisterForm>,
         errors: Option<UserLoginFormValidationErrorMessage>,
-        token: Option<Markup>,
     ) -> Markup {
         let user_register_form = user_register_form.unwrap_or_default();
         let errors = errors.unwrap_or_default();
-        let token = token.unwrap_or_default();
+        let locale = &context_html_builder.locale;
+        let form = context_html_builder
+            .form("register", locale.translate("user-register-submit"))
+            .attach_field(
+                FormField::text("username", locale.translate("user-username"))
+                    .set_value(&user_register_form.username)
+                    .set_autocomplete("username")
+                    .attach_errors(errors.username),
+            )
+            .attach_field(
+                FormField::password("password", locale.translate("user-password"))
+                    .set_autocomplete("new-password")
+                    .attach_errors(errors.password),
+            )
+            .attach_field(
+                FormField::password(
+                    "password_confirm",
+                    locale.translate("user-password-confirm"),
+                )
+                .set_autocomplete("new-password")
+                .attach_errors(errors.password_confirm),
+            );
         context_html_builder
             .attach_title(title.as_str())
             .attach_content(html! {
                 h1 .mt-3 { (title) }
-                form method="post" .form {
-                    (token)
-                    input .form-item type="text" name="username" placeholder="Username" value=(user_register_form.username);
-                    (errors.username.into_error_html())
-                    input .form-item type="password" name="password" placeholder="Password";
-                    (errors.password.into_error_html())
-                    input .form-item type="password" name="password_confirm" placeholder="Confirm password";
-                    (errors.password_confirm.into_error_html())
-                    button .btn .btn-sky-blue .mt-3 type="submit" { "Register" };
-                }
+                (form.build())
             })
             .build()
     }
@@ -133,3 +153,88 @@ impl UserLoginForm {
         self.clone().into()
     }
 }
+
+/// Posted from the user page.
+#[derive(Deserialize)]
+pub struct UserTimeZoneForm {
+    /// IANA name, empty for UTC.
+    pub time_zone: String,
+    pub csrf_token: String,
+}
+
+impl UserTimeZoneForm {
+    /// `None` for an empty choice or a name `chrono-tz` does not know.
+    pub fn as_time_zone(&self) -> Option<String> {
+        self.time_zone
+            .parse::<Tz>()
+            .ok()
+            .map(|time_zone| time_zone.name().to_string())
+    }
+}
+
+/// Posted by the language switcher in the navigation.
+#[derive(Deserialize)]
+pub struct UserLocaleForm {
+    /// Empty to go back to `Accept-Language`.
+    pub locale: String,
+    pub return_to: String,
+    pub csrf_token: String,
+}
+
+impl UserLocaleForm {
+    /// `None` for an empty choice or a language that does not exist.
+    pub fn as_locale(&self) -> Option<String> {
+        Some(self.locale.clone()).filter(|locale| is_available_language(locale))
+    }
+
+    pub fn return_path(&self) -> &str {
+        local_path(&self.return_to)
+    }
+}
+
+/// Posted by the theme switcher in the navigation.
+#[derive(Deserialize)]
+pub struct UserThemeForm {
+    pub theme: String,
+    pub return_to: String,
+    pub csrf_token: String,
+}
+
+impl UserThemeForm {
+    /// Unknown values fall back to following the system.
+    pub fn as_theme(&self) -> Theme {
+        Theme::parse(&self.theme).unwrap_or_default()
+    }
+
+    pub fn return_path(&self) -> &str {
+        local_path(&self.return_to)
+    }
+}
+
+/// Only local paths, so the switcher forms cannot be used as an open redirect. Browsers
+/// read `/\host` like `//host`, so a backslash after the first slash is refused too.
+fn local_path(path: &str) -> &str {
+    let is_local = match path.as_bytes() {
+        [b'/', b'/' | b'\\', ..] => false,
+        [b'/', ..] => path
+            .parse::<Uri>()
+            .is_ok_and(|uri| uri.scheme().is_none() && uri.authority().is_none()),
+        _ => false,
+    };
+    if is_local { path } else { "/" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn return_paths_stay_on_this_site() {
+        for path in ["/", "/bucket-list/", "/user/?tab=account#locale"] {
+            assert_eq!(local_path(path), path);
+        }
+        for path in ["//x", "/\\x", "https://x", "x", "", "/\tx"] {
+            assert_eq!(local_path(path), "/", "{:?}", path);
+        }
+    }
+}
@@ -9,6 +9,10 @@ pub struct UserIdContext {
     pub id: i64,
     pub is_user: bool,
     pub username: String,
+    /// The saved language choice, visitors keep theirs in a cookie instead.
+    pub locale: Option<String>,
+    /// IANA name such as `Europe/London`, `None` shows times in UTC.
+    pub time_zone: Option<String>,
 }
 
 pub struct IdPassword {
@@ -19,6 +23,14 @@ pub struct IdPassword {
 pub struct IdUsername {
     pub id: i64,
     pub username: String,
+    pub locale: Option<String>,
+    pub time_zone: Option<String>,
+}
+
+pub struct UserSummary {
+    pub id: i64,
+    pub username: String,
+    pub active_tokens: i64,
 }
 
 pub struct UserRegisterFormValidated {
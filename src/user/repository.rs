@@ -1,175 +0,0 @@
-use crate::common::context::{Context, ContextError, FromContext};
-use crate::common::db::SqliteClient;
-use crate::user::model::{IdPassword, IdUsername};
-use error_stack::{Report, ResultExt};
-use rusqlite::{Connection, OptionalExtension, named_params};
-use std::sync::MutexGuard;
-use thiserror::Error;
-
-#[derive(Error, Debug)]
-pub enum UserRepositoryError {
-    #[error("Query error")]
-    QueryError,
-    #[error("Row Value error")]
-    RowValueError,
-    #[error("Lock error")]
-    LockError,
-    #[error("Not found error")]
-    NotFoundError,
-}
-
-pub struct UserRepository {
-    sqlite_client: SqliteClient,
-}
-
-impl UserRepository {
-    pub fn new(sqlite_client: SqliteClient) -> Self {
-        Self { sqlite_client }
-    }
-
-    pub fn add_token(
-        &self,
-        token: String,
-        user_id: i64,
-    ) -> Result<(), Report<UserRepositoryError>> {
-        let conn = self.borrow_conn()?;
-
-        conn.execute(
-            include_str!("_sql/add_token.sql"),
-            named_params! {
-                ":token": token,
-                ":user_id": user_id,
-            },
-        )
-        .change_context(UserRepositoryError::QueryError)?;
-
-        Ok(())
-    }
-
-    pub fn delete_token(&self, token: String) -> Result<(), Report<UserRepositoryError>> {
-        let conn = self.borrow_conn()?;
-
-        conn.execute(
-            include_str!("_sql/delete_token.sql"),
-            named_params! {
-                ":token": token,
-            },
-        )
-        .change_context(UserRepositoryError::QueryError)?;
-
-        Ok(())
-    }
-
-    pub fn find_by_token(&self, token: String) -> Result<IdUsername, Report<UserRepositoryError>> {
-        let conn = self.borrow_conn()?;
-
-        let mut stmt = conn
-            .prepare_cached(include_str!("_sql/find_by_token.sql"))
-            .change_context(UserRepositoryError::QueryError)?;
-
-        let row: Option<IdUsername> = stmt
-            .query_one(
-                named_params! {
-                    ":token": token,
-                },
-                |row| {
-                    Ok(IdUsername {
-                        id: row.get("id")?,
-                        username: row.get("username")?,
-                    })
-                },
-            )
-            .optional()
-            .change_context(UserRepositoryError::QueryError)?;
-
-        match row {
-            Some(item) => Ok(item),
-            None => Err(Report::new(UserRepositoryError::NotFoundError)),
-        }
-    }
-
-    pub fn get_user_password(
-        &self,
-        username: String,
-    ) -> Result<IdPassword, Report<UserRepositoryError>> {
-        let conn = self.borrow_conn()?;
-
-        let mut stmt = conn
-            .prepare_cached(include_str!("_sql/get_user_password.sql"))
-            .change_context(UserRepositoryError::QueryError)?;
-
-        let row: Option<IdPassword> = stmt
-            .query_one(
-                named_params! {
-                    ":username": username,
-                },
-                |row| {
-                    Ok(IdPassword {
-                        id: row.get("id")?,
-                        password: row.get("password")?,
-                    })
-                },
-            )
-            .optional()
-            .change_context(UserRepositoryError::QueryError)?;
-
-        match row {
-            Some(item) => Ok(item),
-            None => Err(Report::new(UserRepositoryError::NotFoundError)),
-        }
-    }
-
-    pub fn register_user(
-        &self,
-        username: String,
-        password: Box<[u8]>,
-    ) -> Result<(), Report<UserRepositoryError>> {
-        let conn = self.borrow_conn()?;
-
-        conn.execute(
-            include_str!("_sql/register_user.sql"),
-            named_params! {
-                ":username": username,
-                ":password": password,
-            },
-        )
-        .change_context(UserRepositoryError::QueryError)?;
-
-        Ok(())
-    }
-
-    pub fn username_taken(&self, username: String) -> Result<bool, Report<UserRepositoryError>> {
-        let conn = self.borrow_conn()?;
-
-        let mut stmt = conn
-            .prepare_cached(include_str!("_sql/username_taken.sql"))
-            .change_context(UserRepositoryError::QueryError)?;
-
-        let row: Option<bool> = stmt
-            .query_one(
-                named_params! {
-                    ":username": username,
-                },
-                |row| Ok(row.get("taken")?),
-            )
-            .optional()
-            .change_context(UserRepositoryError::QueryError)?;
-
-        Ok(row.unwrap_or_default())
-    }
-
-    fn borrow_conn(&'_ self) -> Result<MutexGuard<'_, Connection>, Report<UserRepositoryError>> {
-        let guard = self
-            .sqlite_client
-            .get_conn()
-            .lock()
-            .map_err(|_| Report::new(UserRepositoryError::LockError))?;
-        Ok(guard)
-    }
-}
-
-impl FromContext for UserRepository {
-    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
-        Ok(Self::new(ctx.inject().await?))
-    }
-}
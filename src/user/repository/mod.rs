@@ -0,0 +1,221 @@
+use crate::common::config::Config;
+use crate::common::config::database::DatabaseBackend;
+use crate::common::context::{Context, ContextError, FromContext};
+use crate::common::db::SqliteClient;
+use crate::user::model::{IdPassword, IdUsername, UserSummary};
+use async_trait::async_trait;
+use error_stack::{Report, ResultExt};
+use sqlite::SqliteUserRepository;
+use thiserror::Error;
+
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod sqlite;
+
+#[derive(Error, Debug)]
+pub enum UserRepositoryError {
+    #[error("Query error")]
+    QueryError,
+    #[error("Row Value error")]
+    RowValueError,
+    #[error("Lock error")]
+    LockError,
+    #[error("Not found error")]
+    NotFoundError,
+    #[error("Connection error")]
+    ConnectionError,
+}
+
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    /// Adds a login token that expires after 30 days.
+    async fn add_token(
+        &self,
+        token: String,
+        user_id: i64,
+    ) -> Result<(), Report<UserRepositoryError>>;
+
+    async fn delete_token(&self, token: String) -> Result<(), Report<UserRepositoryError>>;
+
+    /// Only finds tokens that have not expired.
+    async fn find_by_token(&self, token: String)
+    -> Result<IdUsername, Report<UserRepositoryError>>;
+
+    async fn get_user_password(
+        &self,
+        username: String,
+    ) -> Result<IdPassword, Report<UserRepositoryError>>;
+
+    async fn register_user(
+        &self,
+        username: String,
+        password: Box<[u8]>,
+    ) -> Result<(), Report<UserRepositoryError>>;
+
+    async fn username_taken(&self, username: String) -> Result<bool, Report<UserRepositoryError>>;
+
+    async fn count_active_tokens(&self) -> Result<i64, Report<UserRepositoryError>>;
+
+    async fn list_users(&self) -> Result<Box<[UserSummary]>, Report<UserRepositoryError>>;
+
+    /// Returns the id of the updated user.
+    async fn update_user_password(
+        &self,
+        username: String,
+        password: Box<[u8]>,
+    ) -> Result<i64, Report<UserRepositoryError>>;
+
+    async fn delete_user_tokens(&self, user_id: i64) -> Result<(), Report<UserRepositoryError>>;
+
+    /// Returns the number of deleted tokens.
+    async fn purge_expired_tokens(&self) -> Result<usize, Report<UserRepositoryError>>;
+
+    /// `None` clears the choice, so the request headers decide again.
+    async fn update_user_locale(
+        &self,
+        user_id: i64,
+        locale: Option<String>,
+    ) -> Result<(), Report<UserRepositoryError>>;
+
+    /// `None` goes back to UTC.
+    async fn update_user_time_zone(
+        &self,
+        user_id: i64,
+        time_zone: Option<String>,
+    ) -> Result<(), Report<UserRepositoryError>>;
+}
+
+/// The repository for the configured `database.backend`.
+pub async fn user_repository(
+    config: &Config,
+) -> Result<Box<dyn UserRepository>, Report<UserRepositoryError>> {
+    match config.database.backend {
+        DatabaseBackend::Sqlite => Ok(Box::new(SqliteUserRepository::new(
+            SqliteClient::fetch(config)
+                .await
+                .change_context(UserRepositoryError::ConnectionError)?,
+        ))),
+        #[cfg(feature = "postgres")]
+        DatabaseBackend::Postgres => Ok(Box::new(postgres::PostgresUserRepository::new(
+            crate::common::db::postgres::PostgresClient::fetch(config)
+                .await
+                .change_context(UserRepositoryError::ConnectionError)?,
+        ))),
+        #[cfg(not(feature = "postgres"))]
+        DatabaseBackend::Postgres => Err(Report::new(UserRepositoryError::ConnectionError)),
+    }
+}
+
+impl<'a> FromContext for Box<dyn UserRepository + 'a> {
+    async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
+        let repository: Box<dyn UserRepository + 'a> = user_repository(&ctx.config)
+            .await
+            .change_context(ContextError::Other)?;
+        Ok(repository)
+    }
+}
+
+/// A repository on a new database for each backend under test: in-memory SQLite, and
+/// Postgres when built with it and `LITTLE_POEM_TEST_DATABASE__URL` is set.
+#[cfg(test)]
+pub async fn test_repositories() -> Vec<Box<dyn UserRepository>> {
+    let sqlite: Box<dyn UserRepository> = Box::new(SqliteUserRepository::new(
+        SqliteClient::new(":memory:".to_string()).unwrap(),
+    ));
+    #[cfg(feature = "postgres")]
+    let postgres = crate::common::db::postgres::test_client()
+        .await
+        .map(|client| Box::new(postgres::PostgresUserRepository::new(client)) as _);
+    #[cfg(not(feature = "postgres"))]
+    let postgres = None;
+    std::iter::once(sqlite).chain(postgres).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn token_logs_the_user_in_until_deleted() {
+        for repo in test_repositories().await {
+            repo.register_user("alice".to_string(), Box::new([1, 2, 3]))
+                .await
+                .unwrap();
+            assert!(repo.username_taken("alice".to_string()).await.unwrap());
+            assert!(!repo.username_taken("bob".to_string()).await.unwrap());
+
+            let id_password = repo.get_user_password("alice".to_string()).await.unwrap();
+            assert_eq!(*id_password.password, [1, 2, 3]);
+
+            repo.add_token("token".to_string(), id_password.id)
+                .await
+                .unwrap();
+            let id_username = repo.find_by_token("token".to_string()).await.unwrap();
+            assert_eq!(id_username.id, id_password.id);
+            assert_eq!(id_username.username, "alice");
+            assert_eq!(repo.count_active_tokens().await.unwrap(), 1);
+
+            repo.delete_token("token".to_string()).await.unwrap();
+            let err = repo.find_by_token("token".to_string()).await.err().unwrap();
+            assert!(matches!(
+                err.current_context(),
+                UserRepositoryError::NotFoundError
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn preferences_are_saved_on_the_user() {
+        for repo in test_repositories().await {
+            repo.register_user("alice".to_string(), Box::new([]))
+                .await
+                .unwrap();
+            let id = repo
+                .get_user_password("alice".to_string())
+                .await
+                .unwrap()
+                .id;
+            repo.add_token("token".to_string(), id).await.unwrap();
+
+            repo.update_user_locale(id, Some("cy".to_string()))
+                .await
+                .unwrap();
+            repo.update_user_time_zone(id, Some("Europe/London".to_string()))
+                .await
+                .unwrap();
+            let id_username = repo.find_by_token("token".to_string()).await.unwrap();
+            assert_eq!(id_username.locale.as_deref(), Some("cy"));
+            assert_eq!(id_username.time_zone.as_deref(), Some("Europe/London"));
+        }
+    }
+
+    #[tokio::test]
+    async fn password_reset_goes_through_the_username() {
+        for repo in test_repositories().await {
+            repo.register_user("alice".to_string(), Box::new([1]))
+                .await
+                .unwrap();
+            let id = repo
+                .update_user_password("alice".to_string(), Box::new([2]))
+                .await
+                .unwrap();
+            repo.add_token("token".to_string(), id).await.unwrap();
+            repo.delete_user_tokens(id).await.unwrap();
+
+            let users = repo.list_users().await.unwrap();
+            let alice = users.iter().find(|user| user.id == id).unwrap();
+            assert_eq!(alice.active_tokens, 0);
+            let id_password = repo.get_user_password("alice".to_string()).await.unwrap();
+            assert_eq!(*id_password.password, [2]);
+
+            let err = repo
+                .update_user_password("bob".to_string(), Box::new([]))
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err.current_context(),
+                UserRepositoryError::NotFoundError
+            ));
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use crate::common::db::postgres::PostgresClient;
+use crate::user::model::{IdPassword, IdUsername, UserSummary};
+use crate::user::repository::{UserRepository, UserRepositoryError};
+use async_trait::async_trait;
+use error_stack::{Report, ResultExt};
+
+pub struct PostgresUserRepository {
+    postgres_client: PostgresClient,
+}
+
+impl PostgresUserRepository {
+    pub fn new(postgres_client: PostgresClient) -> Self {
+        Self { postgres_client }
+    }
+}
+
+#[async_trait]
+impl UserRepository for PostgresUserRepository {
+    async fn add_token(
+        &self,
+        token: String,
+        user_id: i64,
+    ) -> Result<(), Report<UserRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        client
+            .execute(
+                include_str!("../_sql/postgres/add_token.sql"),
+                &[&user_id, &token],
+            )
+            .await
+            .change_context(UserRepositoryError::QueryError)?;
+        Ok(())
+    }
+
+    async fn delete_token(&self, token: String) -> Result<(), Report<UserRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        client
+            .execute(include_str!("../_sql/postgres/delete_token.sql"), &[&token])
+            .await
+            .change_context(UserRepositoryError::QueryError)?;
+        Ok(())
+    }
+
+    async fn find_by_token(
+        &self,
+        token: String,
+    ) -> Result<IdUsername, Report<UserRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        let row = client
+            .query_opt(
+                include_str!("../_sql/postgres/find_by_token.sql"),
+                &[&token],
+            )
+            .await
+            .change_context(UserRepositoryError::QueryError)?
+            .ok_or_else(|| Report::new(UserRepositoryError::NotFoundError))?;
+
+        Ok(IdUsername {
+            id: row
+                .try_get("id")
+                .change_context(UserRepositoryError::RowValueError)?,
+            username: row
+                .try_get("username")
+                .change_context(UserRepositoryError::RowValueError)?,
+            locale: row
+                .try_get("locale")
+                .change_context(UserRepositoryError::RowValueError)?,
+            time_zone: row
+                .try_get("time_zone")
+                .change_context(UserRepositoryError::RowValueError)?,
+        })
+    }
+
+    async fn get_user_password(
+        &self,
+        username: String,
+    ) -> Result<IdPassword, Report<UserRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        let row = client
+            .query_opt(
+                include_str!("../_sql/postgres/get_user_password.sql"),
+                &[&username],
+            )
+            .await
+            .change_context(UserRepositoryError::QueryError)?
+            .ok_or_else(|| Report::new(UserRepositoryError::NotFoundError))?;
+
+        Ok(IdPassword {
+            id: row
+                .try_get("id")
+                .change_context(UserRepositoryError::RowValueError)?,
+            password: row
+                .try_get::<_, Vec<u8>>("password")
+                .change_context(UserRepositoryError::RowValueError)?
+                .into(),
+        })
+    }
+
+    async fn register_user(
+        &self,
+        username: String,
+        password: Box<[u8]>,
+    ) -> Result<(), Report<UserRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        client
+            .execute(
+                include_str!("../_sql/postgres/register_user.sql"),
+                &[&username, &password.as_ref()],
+            )
+            .await
+            .change_context(UserRepositoryError::QueryError)?;
+        Ok(())
+    }
+
+    async fn username_taken(&self, username: String) -> Result<bool, Report<UserRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        client
+            .query_one(
+                include_str!("../_sql/postgres/username_taken.sql"),
+                &[&username],
+            )
+            .await
+            .change_context(UserRepositoryError::QueryError)?
+            .try_get("taken")
+            .change_context(UserRepositoryError::RowValueError)
+    }
+
+    async fn count_active_tokens(&self) -> Result<i64, Report<UserRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        client
+            .query_one(
+                include_str!("../_sql/postgres/count_active_tokens.sql"),
+                &[],
+            )
+            .await
+            .change_context(UserRepositoryError::QueryError)?
+            .try_get("active")
+            .change_context(UserRepositoryError::RowValueError)
+    }
+
+    async fn list_users(&self) -> Result<Box<[UserSummary]>, Report<UserRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        let rows = client
+            .query(include_str!("../_sql/postgres/list_users.sql"), &[])
+            .await
+            .change_context(UserRepositoryError::QueryError)?;
+
+        let mut users: Vec<UserSummary> = Vec::with_capacity(rows.len());
+        for row in rows {
+            users.push(UserSummary {
+                id: row
+                    .try_get("id")
+                    .change_context(UserRepositoryError::RowValueError)?,
+                username: row
+                    .try_get("username")
+                    .change_context(UserRepositoryError::RowValueError)?,
+                active_tokens: row
+                    .try_get("active_tokens")
+                    .change_context(UserRepositoryError::RowValueError)?,
+            });
+        }
+
+        Ok(users.into())
+    }
+
+    async fn update_user_password(
+        &self,
+        username: String,
+        password: Box<[u8]>,
+    ) -> Result<i64, Report<UserRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        client
+            .query_opt(
+                include_str!("../_sql/postgres/update_user_password.sql"),
+                &[&username, &password.as_ref()],
+            )
+            .await
+            .change_context(UserRepositoryError::QueryError)?
+            .ok_or_else(|| Report::new(UserRepositoryError::NotFoundError))?
+            .try_get("id")
+            .change_context(UserRepositoryError::RowValueError)
+    }
+
+    async fn delete_user_tokens(&self, user_id: i64) -> Result<(), Report<UserRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        client
+            .execute(
+                include_str!("../_sql/postgres/delete_user_tokens.sql"),
+                &[&user_id],
+            )
+            .await
+            .change_context(UserRepositoryError::QueryError)?;
+        Ok(())
+    }
+
+    async fn purge_expired_tokens(&self) -> Result<usize, Report<UserRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        let purged = client
+            .execute(
+                include_str!("../_sql/postgres/purge_expired_tokens.sql"),
+                &[],
+            )
+            .await
+            .change_context(UserRepositoryError::QueryError)?;
+        Ok(purged as usize)
+    }
+
+    async fn update_user_locale(
+        &self,
+        user_id: i64,
+        locale: Option<String>,
+    ) -> Result<(), Report<UserRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        client
+            .execute(
+                include_str!("../_sql/postgres/update_user_locale.sql"),
+                &[&user_id, &locale],
+            )
+            .await
+            .change_context(UserRepositoryError::QueryError)?;
+        Ok(())
+    }
+
+    async fn update_user_time_zone(
+        &self,
+        user_id: i64,
+        time_zone: Option<String>,
+    ) -> Result<(), Report<UserRepositoryError>> {
+        let client = self.postgres_client.get_client().await;
+        client
+            .execute(
+                include_str!("../_sql/postgres/update_user_time_zone.sql"),
+                &[&user_id, &time_zone],
+            )
+            .await
+            .change_context(UserRepositoryError::QueryError)?;
+        Ok(())
+    }
+}
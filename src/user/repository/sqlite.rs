@@ -0,0 +1,293 @@
+use crate::common::db::{SqliteClient, SqliteClientError, SqliteConnection};
+use crate::common::metrics::Metrics;
+use crate::user::model::{IdPassword, IdUsername, UserSummary};
+use crate::user::repository::{UserRepository, UserRepositoryError};
+use async_trait::async_trait;
+use error_stack::{Report, ResultExt};
+use rusqlite::{OptionalExtension, named_params};
+use std::time::Instant;
+
+pub struct SqliteUserRepository {
+    sqlite_client: SqliteClient,
+}
+
+impl SqliteUserRepository {
+    pub fn new(sqlite_client: SqliteClient) -> Self {
+        Self { sqlite_client }
+    }
+
+    fn borrow_conn(&'_ self) -> Result<SqliteConnection<'_>, Report<UserRepositoryError>> {
+        let start = Instant::now();
+        let guard = self.sqlite_client.lock().map_err(|err| {
+            let context = match err.current_context() {
+                SqliteClientError::Closed => UserRepositoryError::ConnectionError,
+                _ => UserRepositoryError::LockError,
+            };
+            err.change_context(context)
+        })?;
+        Metrics::get().record_sqlite_lock_wait(start.elapsed());
+        Ok(guard)
+    }
+}
+
+#[async_trait]
+impl UserRepository for SqliteUserRepository {
+    async fn add_token(
+        &self,
+        token: String,
+        user_id: i64,
+    ) -> Result<(), Report<UserRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        conn.execute(
+            include_str!("../_sql/add_token.sql"),
+            named_params! {
+                ":token": token,
+                ":user_id": user_id,
+            },
+        )
+        .change_context(UserRepositoryError::QueryError)?;
+
+        Ok(())
+    }
+
+    async fn delete_token(&self, token: String) -> Result<(), Report<UserRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        conn.execute(
+            include_str!("../_sql/delete_token.sql"),
+            named_params! {
+                ":token": token,
+            },
+        )
+        .change_context(UserRepositoryError::QueryError)?;
+
+        Ok(())
+    }
+
+    async fn find_by_token(
+        &self,
+        token: String,
+    ) -> Result<IdUsername, Report<UserRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        let mut stmt = conn
+            .prepare_cached(include_str!("../_sql/find_by_token.sql"))
+            .change_context(UserRepositoryError::QueryError)?;
+
+        let row: Option<IdUsername> = stmt
+            .query_one(
+                named_params! {
+                    ":token": token,
+                },
+                |row| {
+                    Ok(IdUsername {
+                        id: row.get("id")?,
+                        username: row.get("username")?,
+                        locale: row.get("locale")?,
+                        time_zone: row.get("time_zone")?,
+                    })
+                },
+            )
+            .optional()
+            .change_context(UserRepositoryError::QueryError)?;
+
+        match row {
+            Some(item) => Ok(item),
+            None => Err(Report::new(UserRepositoryError::NotFoundError)),
+        }
+    }
+
+    async fn get_user_password(
+        &self,
+        username: String,
+    ) -> Result<IdPassword, Report<UserRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        let mut stmt = conn
+            .prepare_cached(include_str!("../_sql/get_user_password.sql"))
+            .change_context(UserRepositoryError::QueryError)?;
+
+        let row: Option<IdPassword> = stmt
+            .query_one(
+                named_params! {
+                    ":username": username,
+                },
+                |row| {
+                    Ok(IdPassword {
+                        id: row.get("id")?,
+                        password: row.get("password")?,
+                    })
+                },
+            )
+            .optional()
+            .change_context(UserRepositoryError::QueryError)?;
+
+        match row {
+            Some(item) => Ok(item),
+            None => Err(Report::new(UserRepositoryError::NotFoundError)),
+        }
+    }
+
+    async fn register_user(
+        &self,
+        username: String,
+        password: Box<[u8]>,
+    ) -> Result<(), Report<UserRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        conn.execute(
+            include_str!("../_sql/register_user.sql"),
+            named_params! {
+                ":username": username,
+                ":password": password,
+            },
+        )
+        .change_context(UserRepositoryError::QueryError)?;
+
+        Ok(())
+    }
+
+    async fn username_taken(&self, username: String) -> Result<bool, Report<UserRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        let mut stmt = conn
+            .prepare_cached(include_str!("../_sql/username_taken.sql"))
+            .change_context(UserRepositoryError::QueryError)?;
+
+        let row: Option<bool> = stmt
+            .query_one(
+                named_params! {
+                    ":username": username,
+                },
+                |row| Ok(row.get("taken")?),
+            )
+            .optional()
+            .change_context(UserRepositoryError::QueryError)?;
+
+        Ok(row.unwrap_or_default())
+    }
+
+    async fn count_active_tokens(&self) -> Result<i64, Report<UserRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        let mut stmt = conn
+            .prepare_cached(include_str!("../_sql/count_active_tokens.sql"))
+            .change_context(UserRepositoryError::QueryError)?;
+
+        stmt.query_one([], |row| row.get("active"))
+            .change_context(UserRepositoryError::QueryError)
+    }
+
+    async fn list_users(&self) -> Result<Box<[UserSummary]>, Report<UserRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        let mut stmt = conn
+            .prepare_cached(include_str!("../_sql/list_users.sql"))
+            .change_context(UserRepositoryError::QueryError)?;
+
+        let user_iter = stmt
+            .query_map([], |row| {
+                Ok(UserSummary {
+                    id: row.get("id")?,
+                    username: row.get("username")?,
+                    active_tokens: row.get("active_tokens")?,
+                })
+            })
+            .change_context(UserRepositoryError::RowValueError)?;
+
+        let mut users: Vec<UserSummary> = Vec::new();
+        for user in user_iter {
+            users.push(user.change_context(UserRepositoryError::RowValueError)?);
+        }
+
+        Ok(users.into())
+    }
+
+    async fn update_user_password(
+        &self,
+        username: String,
+        password: Box<[u8]>,
+    ) -> Result<i64, Report<UserRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        let mut stmt = conn
+            .prepare_cached(include_str!("../_sql/update_user_password.sql"))
+            .change_context(UserRepositoryError::QueryError)?;
+
+        let row: Option<i64> = stmt
+            .query_one(
+                named_params! {
+                    ":username": username,
+                    ":password": password,
+                },
+                |row| row.get("id"),
+            )
+            .optional()
+            .change_context(UserRepositoryError::QueryError)?;
+
+        match row {
+            Some(id) => Ok(id),
+            None => Err(Report::new(UserRepositoryError::NotFoundError)),
+        }
+    }
+
+    async fn delete_user_tokens(&self, user_id: i64) -> Result<(), Report<UserRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        conn.execute(
+            include_str!("../_sql/delete_user_tokens.sql"),
+            named_params! {
+                ":user_id": user_id,
+            },
+        )
+        .change_context(UserRepositoryError::QueryError)?;
+
+        Ok(())
+    }
+
+    async fn purge_expired_tokens(&self) -> Result<usize, Report<UserRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        conn.execute(include_str!("../_sql/purge_expired_tokens.sql"), [])
+            .change_context(UserRepositoryError::QueryError)
+    }
+
+    async fn update_user_locale(
+        &self,
+        user_id: i64,
+        locale: Option<String>,
+    ) -> Result<(), Report<UserRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        conn.execute(
+            include_str!("../_sql/update_user_locale.sql"),
+            named_params! {
+                ":user_id": user_id,
+                ":locale": locale,
+            },
+        )
+        .change_context(UserRepositoryError::QueryError)?;
+
+        Ok(())
+    }
+
+    async fn update_user_time_zone(
+        &self,
+        user_id: i64,
+        time_zone: Option<String>,
+    ) -> Result<(), Report<UserRepositoryError>> {
+        let conn = self.borrow_conn()?;
+
+        conn.execute(
+            include_str!("../_sql/update_user_time_zone.sql"),
+            named_params! {
+                ":user_id": user_id,
+                ":time_zone": time_zone,
+            },
+        )
+        .change_context(UserRepositoryError::QueryError)?;
+
+        Ok(())
+    }
+}
@@ -1,23 +1,34 @@
 use crate::common::adapter::unified;
 use crate::common::context::user::{JustDep, UserDep};
 use crate::common::cookie_builder::CookieBuilderExt;
-use crate::common::csrf::{CsrfError, CsrfTokenHtml, CsrfVerifierError};
+use crate::common::csrf::{CsrfError, CsrfVerifierError};
 use crate::common::flash::{Flash, FlashMessage};
 use crate::common::html::context_html::ContextHtmlBuilder;
+use crate::common::html::form::FormField;
+use crate::common::html::navigation::{
+    NavigationItem, NavigationSection, NavigationVisibility, register_navigation,
+};
+use crate::common::html::theme::{THEME_COOKIE, Theme};
+use crate::common::icon::{login_icon, user_icon};
+use crate::common::locale::{LOCALE_COOKIE, LocaleExtForText};
+use crate::common::proxy::ClientInfo;
 use crate::user::flag::{LoginFlag, LogoutFlag};
 use crate::user::form::{
-    UserLoginForm, UserLoginFormResult, UserRegisterForm, UserRegisterFormResult,
+    UserLocaleForm, UserLoginForm, UserLoginFormResult, UserRegisterForm, UserRegisterFormResult,
+    UserThemeForm, UserTimeZoneForm,
 };
-use crate::user::service::{UserLoginService, UserRegisterService};
-use chrono::TimeDelta;
+use crate::user::service::{UserLocaleService, UserLoginService, UserRegisterService};
+use chrono::{TimeDelta, Utc};
+use chrono_tz::TZ_VARIANTS;
 use error_stack::Report;
 use maud::{Markup, html};
 use poem::error::ResponseError;
 use poem::http::StatusCode;
+use poem::i18n::I18NArgs;
 use poem::session::Session;
 use poem::web::cookie::{Cookie, CookieJar};
-use poem::web::{CsrfToken, CsrfVerifier, Form, Redirect};
-use poem::{IntoResponse, Route, get, handler};
+use poem::web::{CsrfVerifier, Form, Redirect};
+use poem::{IntoResponse, Route, get, handler, post};
 
 pub const USER_PATH: &'static str = "/user/";
 
@@ -25,49 +36,75 @@ pub const USER_PATH: &'static str = "/user/";
 async fn display_user(
     UserDep(context_html_builder, user, _): UserDep<ContextHtmlBuilder>,
 ) -> Markup {
+    let locale = &context_html_builder.locale;
+    let username = || I18NArgs::default().set("username", user.username.clone());
     let title = if user.is_user {
-        format!("User: {}", user.username)
+        locale.translate_with_args("user-title", username)
     } else {
-        "Visitor".to_string()
+        locale.translate("user-visitor-title")
     };
+    let mut time_zones = vec![(String::new(), locale.translate("user-time-zone-utc"))];
+    time_zones.extend(
+        TZ_VARIANTS
+            .iter()
+            .map(|time_zone| (time_zone.name().to_string(), time_zone.name().to_string())),
+    );
+    let time_zone_form = context_html_builder
+        .form("account", locale.translate("user-time-zone-submit"))
+        .set_action("/user/time-zone/")
+        .attach_field(
+            FormField::select("time_zone", locale.translate("user-time-zone"), time_zones)
+                .set_value(user.time_zone.as_deref().unwrap_or_default()),
+        );
 
     context_html_builder
         .attach_title(title.as_str())
         .set_current_tag("user")
         .attach_content(html! {
             h1 .mt-3 { (title) }
-            p { "Welcome to the user page!" }
+            p { (locale.translate("user-welcome")) }
+            p {
+                (locale.translate_with_args("user-current-time", || {
+                    I18NArgs::default()
+                        .set("time", context_html_builder.format_datetime(&Utc::now()))
+                        .set("timeZone", context_html_builder.time_zone.name())
+                }))
+            }
             @if user.is_user {
-                p { "You are logged in as a user '" (user.username) "'." }
-                p { "You can log out by clicking the button below." }
-                a .btn .btn-sky-blue .mt-3 href="/user/logout/" { "Log out" }
+                p { (locale.translate_with_args("user-logged-in-as", username)) }
+                (time_zone_form.build())
+                p { (locale.translate("user-logout-hint")) }
+                a .btn .btn-sky-blue .mt-3 href="/user/logout/" { (locale.translate("user-logout")) }
             } @else {
-                p { "You are logged in as a visitor." }
-                p { "You can log in as a user by clicking the button below." }
-                a .btn .btn-sky-blue .mt-3 href="/user/login/" { "Log in as a user" }
+                p { (locale.translate("user-visitor-logged-in")) }
+                p { (locale.translate("user-visitor-login-hint")) }
+                a .btn .btn-sky-blue .mt-3 href="/user/login/" { (locale.translate("user-login-as-user")) }
             }
         })
         .build()
 }
 
 #[handler]
-async fn login(
-    JustDep(context_html_builder, _): JustDep<ContextHtmlBuilder>,
-    csrf_token: &CsrfToken,
-) -> Markup {
-    let title = "Login".to_string();
+async fn login(JustDep(context_html_builder, _): JustDep<ContextHtmlBuilder>) -> Markup {
+    let locale = &context_html_builder.locale;
+    let title = locale.translate("user-login-title");
+    let form = context_html_builder
+        .form("login", locale.translate("user-login-submit"))
+        .attach_field(
+            FormField::text("username", locale.translate("user-username"))
+                .set_autocomplete("username"),
+        )
+        .attach_field(
+            FormField::password("password", locale.translate("user-password"))
+                .set_autocomplete("current-password"),
+        );
     context_html_builder
         .attach_title(title.as_str())
         .attach_content(html! {
             h1 .mt-3 { (title) }
-            form method="post" .form {
-                (csrf_token.as_html())
-                input .form-item type="text" name="username" placeholder="Username";
-                input .form-item type="password" name="password" placeholder="Password";
-                button .btn .btn-sky-blue .mt-3 type="submit" { "Login" };
-            }
-            p { "If you don't have an account, you can register by clicking the button below." }
-            a .btn .btn-sky-blue .mt-3 href="/user/register/" { "Register" }
+            (form.build())
+            p { (locale.translate("user-register-hint")) }
+            a .btn .btn-sky-blue .mt-3 href="/user/register/" { (locale.translate("user-register-title")) }
         })
         .build()
 }
@@ -93,34 +130,37 @@ async fn login_post(
     session: &Session,
     cookie_jar: &CookieJar,
     csrf_verifier: &CsrfVerifier,
+    client: ClientInfo,
 ) -> LoginPostResponse {
     unified(async {
         csrf_verifier
             .verify(data.csrf_token.as_str())
             .map_err(|err| LoginPostResponse::Csrf(err))?;
         if let UserLoginFormResult(Ok(data)) = data.as_validated() {
-            let token = user_login.validate_login(
-                data.username.as_str().to_string(),
-                data.password.as_str().to_string(),
-            );
+            let token = user_login
+                .validate_login(
+                    data.username.as_str().to_string(),
+                    data.password.as_str().to_string(),
+                )
+                .await;
             if let Some(token) = token {
                 let new_cookie = Cookie::new_with_str("login-token", token)
                     .into_builder()
                     .path("/")
-                    .expires_by_delta(TimeDelta::days(30))
-                    .build();
+                    .expires_by_delta(TimeDelta::days(30));
+                let new_cookie = if client.is_secure() {
+                    new_cookie.secure().build()
+                } else {
+                    new_cookie.build()
+                };
 
                 cookie_jar.add(new_cookie);
-                session.flash(Flash::Success {
-                    msg: "Login succeeded".to_string(),
-                });
+                session.flash(Flash::success("flash-login-succeeded"));
                 return Ok(LoginPostResponse::Redirect(Redirect::see_other("/user/")));
             }
         }
 
-        session.flash(Flash::Error {
-            msg: "Login failed".to_string(),
-        });
+        session.flash(Flash::error("flash-login-failed"));
         Err(LoginPostResponse::Redirect(Redirect::see_other(
             "/user/login/",
         )))
@@ -134,25 +174,21 @@ async fn logout(
     session: &Session,
     cookie: &CookieJar,
 ) -> Redirect {
-    user_login_service.logout();
+    user_login_service.logout().await;
     cookie.remove("login-token");
-    session.flash(Flash::Success {
-        msg: "Logout succeeded".to_string(),
-    });
+    session.flash(Flash::success("flash-logout-succeeded"));
     Redirect::see_other("/user/")
 }
 
 #[handler]
 async fn register(
     JustDep(context_html_builder, _): JustDep<ContextHtmlBuilder, LoginFlag>,
-    csrf_token: &CsrfToken,
 ) -> Markup {
     UserRegisterForm::html_form(
-        "Register".to_string(),
+        context_html_builder.locale.translate("user-register-title"),
         &context_html_builder,
         None,
         None,
-        Some(csrf_token.as_html()),
     )
 }
 
@@ -181,7 +217,6 @@ async fn register_post(
     JustDep(context_html_builder, _): JustDep<ContextHtmlBuilder>,
     session: &Session,
     csrf_verifier: &CsrfVerifier,
-    csrf_token: &CsrfToken,
 ) -> RegisterPostResponse {
     unified(async {
         csrf_verifier
@@ -191,20 +226,19 @@ async fn register_post(
             data.as_validated(&user_register_service).await;
         match validated_data {
             Ok(data) => {
-                if user_register_service.register_user(
-                    data.username.as_str().to_string(),
-                    data.password.as_str().to_string(),
-                ) {
-                    session.flash(Flash::Success {
-                        msg: "Register succeeded".to_string(),
-                    });
+                if user_register_service
+                    .register_user(
+                        data.username.as_str().to_string(),
+                        data.password.as_str().to_string(),
+                    )
+                    .await
+                {
+                    session.flash(Flash::success("flash-register-succeeded"));
                     Ok(RegisterPostResponse::Redirect(Redirect::see_other(
                         "/user/login/",
                     )))
                 } else {
-                    session.flash(Flash::Error {
-                        msg: "Register failed".to_string(),
-                    });
+                    session.flash(Flash::error("flash-register-failed"));
                     Err(RegisterPostResponse::Redirect(Redirect::see_other(
                         "/user/register/",
                     )))
@@ -212,11 +246,10 @@ async fn register_post(
             }
             Err(err) => Err(RegisterPostResponse::MarkupValidationError(
                 UserRegisterForm::html_form(
-                    "Register".to_string(),
+                    context_html_builder.locale.translate("user-register-title"),
                     &context_html_builder,
                     Some(data),
                     Some((err, &context_html_builder.locale).into()),
-                    Some(csrf_token.as_html()),
                 ),
             )),
         }
@@ -224,10 +257,142 @@ async fn register_post(
     .await
 }
 
+enum PreferencePostResponse {
+    Redirect(Redirect),
+    Csrf(Report<CsrfError>),
+}
+
+impl IntoResponse for PreferencePostResponse {
+    fn into_response(self) -> poem::Response {
+        match self {
+            Self::Redirect(redirect) => redirect.into_response(),
+            Self::Csrf(csrf) => csrf.current_context().as_response(),
+        }
+    }
+}
+
+/// A site-wide cookie kept for a year, `None` expires it. `CookieJar::remove` is not
+/// used as its removal has no path, so browsers would keep the `/` cookie.
+fn preference_cookie(name: &str, value: Option<&str>, client: &ClientInfo) -> Cookie {
+    let expires = match value {
+        Some(_) => TimeDelta::days(365),
+        None => TimeDelta::days(-1),
+    };
+    let new_cookie = Cookie::new_with_str(name, value.unwrap_or_default())
+        .into_builder()
+        .path("/")
+        .expires_by_delta(expires);
+    if client.is_secure() {
+        new_cookie.secure().build()
+    } else {
+        new_cookie.build()
+    }
+}
+
+#[handler]
+async fn locale_post(
+    UserDep(user_locale_service, user, _): UserDep<UserLocaleService>,
+    Form(data): Form<UserLocaleForm>,
+    session: &Session,
+    cookie_jar: &CookieJar,
+    csrf_verifier: &CsrfVerifier,
+    client: ClientInfo,
+) -> PreferencePostResponse {
+    unified(async {
+        csrf_verifier
+            .verify(data.csrf_token.as_str())
+            .map_err(PreferencePostResponse::Csrf)?;
+        let locale = data.as_locale();
+        cookie_jar.add(preference_cookie(LOCALE_COOKIE, locale.as_deref(), &client));
+
+        if user.is_user && !user_locale_service.save_locale(user.id, locale).await {
+            session.flash(Flash::error("flash-language-failed"));
+        }
+        Ok(PreferencePostResponse::Redirect(Redirect::see_other(
+            data.return_path(),
+        )))
+    })
+    .await
+}
+
+#[handler]
+async fn theme_post(
+    Form(data): Form<UserThemeForm>,
+    cookie_jar: &CookieJar,
+    csrf_verifier: &CsrfVerifier,
+    client: ClientInfo,
+) -> PreferencePostResponse {
+    unified(async {
+        csrf_verifier
+            .verify(data.csrf_token.as_str())
+            .map_err(PreferencePostResponse::Csrf)?;
+        let theme = data.as_theme();
+        cookie_jar.add(preference_cookie(
+            THEME_COOKIE,
+            (theme != Theme::System).then_some(theme.as_str()),
+            &client,
+        ));
+        Ok(PreferencePostResponse::Redirect(Redirect::see_other(
+            data.return_path(),
+        )))
+    })
+    .await
+}
+
+#[handler]
+async fn time_zone_post(
+    UserDep(user_locale_service, user, _): UserDep<UserLocaleService>,
+    Form(data): Form<UserTimeZoneForm>,
+    session: &Session,
+    csrf_verifier: &CsrfVerifier,
+) -> PreferencePostResponse {
+    unified(async {
+        csrf_verifier
+            .verify(data.csrf_token.as_str())
+            .map_err(PreferencePostResponse::Csrf)?;
+        if user.is_user {
+            session.flash(
+                if user_locale_service
+                    .save_time_zone(user.id, data.as_time_zone())
+                    .await
+                {
+                    Flash::success("flash-time-zone-saved")
+                } else {
+                    Flash::error("flash-time-zone-failed")
+                },
+            );
+        }
+        Ok(PreferencePostResponse::Redirect(Redirect::see_other(
+            USER_PATH,
+        )))
+    })
+    .await
+}
+
 pub fn route_user() -> Route {
+    register_navigation(
+        NavigationItem::new("nav-user", USER_PATH, "user")
+            .set_order(900)
+            .attach_icon(user_icon),
+    );
+    register_navigation(
+        NavigationItem::new("nav-hello-user", USER_PATH, "user")
+            .set_visibility(NavigationVisibility::UserOnly)
+            .set_section(NavigationSection::Account),
+    );
+    register_navigation(
+        NavigationItem::new("nav-visitor-login", "/user/login/", "login")
+            .attach_icon(login_icon)
+            .set_visibility(NavigationVisibility::VisitorOnly)
+            .set_section(NavigationSection::Account),
+    );
+
     Route::new()
         .at("/", get(display_user))
         .at("/login/", get(login).post(login_post))
         .at("/logout/", get(logout))
         .at("/register/", get(register).post(register_post))
+        .at("/locale/", post(locale_post))
+        .at("/theme/", post(theme_post))
+        .at("/time-zone/", post(time_zone_post))
 }
@@ -1,44 +1,50 @@
 use crate::common::context::user::{FromUserContext, UserContext};
 use crate::common::context::{Context, ContextError, FromContext};
+use crate::common::metrics::Metrics;
 use crate::common::password::Password;
-use crate::user::model::{IdUsername, UserIdContext};
-use crate::user::repository::UserRepository;
+use crate::user::model::{IdUsername, UserIdContext, UserSummary};
+use crate::user::repository::{UserRepository, UserRepositoryError};
 use cjtoolkit_structured_validator::types::username::IsUsernameTakenAsync;
-use error_stack::Report;
+use error_stack::{Report, ResultExt};
+use thiserror::Error;
 use uuid::Uuid;
 
 pub struct UserCheckService {
-    user_repository: UserRepository,
+    user_repository: Box<dyn UserRepository>,
     token_cookie: Option<String>,
 }
 
 impl UserCheckService {
-    pub fn new(user_repository: UserRepository, token_cookie: Option<String>) -> Self {
+    pub fn new(user_repository: Box<dyn UserRepository>, token_cookie: Option<String>) -> Self {
         Self {
             user_repository,
             token_cookie,
         }
     }
 
-    pub fn get_user_context(&self) -> UserIdContext {
-        if let Some(id_username) = self.is_logged_in() {
+    pub async fn get_user_context(&self) -> UserIdContext {
+        if let Some(id_username) = self.is_logged_in().await {
             UserIdContext {
                 id: id_username.id,
                 is_user: true,
                 username: id_username.username,
+                locale: id_username.locale,
+                time_zone: id_username.time_zone,
             }
         } else {
             UserIdContext {
                 id: 0,
                 is_user: false,
                 username: "Visitor".to_string(),
+                locale: None,
+                time_zone: None,
             }
         }
     }
 
-    fn is_logged_in(&self) -> Option<IdUsername> {
+    async fn is_logged_in(&self) -> Option<IdUsername> {
         if let Some(token) = &self.token_cookie {
-            if let Ok(id_username) = self.user_repository.find_by_token(token.clone()) {
+            if let Ok(id_username) = self.user_repository.find_by_token(token.clone()).await {
                 return Some(id_username);
             }
         }
@@ -48,19 +54,25 @@ impl UserCheckService {
 }
 
 pub struct UserLoginService {
-    user_repository: UserRepository,
+    user_repository: Box<dyn UserRepository>,
     token_cookie: Option<String>,
 }
 
 impl UserLoginService {
-    fn new(user_repository: UserRepository, token_cookie: Option<String>) -> Self {
+    fn new(user_repository: Box<dyn UserRepository>, token_cookie: Option<String>) -> Self {
         Self {
             user_repository,
             token_cookie,
         }
     }
-    pub fn validate_login(&self, username: String, password: String) -> Option<String> {
-        if let Ok(id_password) = self.user_repository.get_user_password(username) {
+    pub async fn validate_login(&self, username: String, password: String) -> Option<String> {
+        let token = self.attempt_login(username, password).await;
+        Metrics::get().record_login(token.is_some());
+        token
+    }
+
+    async fn attempt_login(&self, username: String, password: String) -> Option<String> {
+        if let Ok(id_password) = self.user_repository.get_user_password(username).await {
             let password_status = Password::verify_password(id_password.password, password);
             if let Ok(password_status) = password_status {
                 if password_status.is_valid() {
@@ -69,6 +81,7 @@ impl UserLoginService {
                     if self
                         .user_repository
                         .add_token(uuid_token.clone(), id_password.id)
+                        .await
                         .is_err()
                     {
                         return None;
@@ -82,9 +95,12 @@ impl UserLoginService {
         None
     }
 
-    pub fn logout(&self) -> bool {
+    pub async fn logout(&self) -> bool {
         if let Some(token) = &self.token_cookie {
-            self.user_repository.delete_token(token.clone()).is_ok()
+            self.user_repository
+                .delete_token(token.clone())
+                .await
+                .is_ok()
         } else {
             false
         }
@@ -92,15 +108,15 @@ impl UserLoginService {
 }
 
 pub struct UserRegisterService {
-    user_repository: UserRepository,
+    user_repository: Box<dyn UserRepository>,
 }
 
 impl UserRegisterService {
-    pub fn new(user_repository: UserRepository) -> Self {
+    pub fn new(user_repository: Box<dyn UserRepository>) -> Self {
         Self { user_repository }
     }
 
-    pub fn register_user(&self, username: String, password: String) -> bool {
+    pub async fn register_user(&self, username: String, password: String) -> bool {
         let password = match Password::hash_password(password) {
             Ok(password) => password,
             Err(_) => return false,
@@ -112,6 +128,7 @@ impl UserRegisterService {
 
         self.user_repository
             .register_user(username, password)
+            .await
             .is_ok()
     }
 }
@@ -120,11 +137,72 @@ impl IsUsernameTakenAsync for UserRegisterService {
     async fn is_username_taken_async(&self, username: &str) -> bool {
         self.user_repository
             .username_taken(username.to_string())
+            .await
             .ok()
             .unwrap_or_default()
     }
 }
 
+#[derive(Error, Debug)]
+pub enum UserAdminError {
+    #[error("User not found")]
+    NotFound,
+    #[error("Password error")]
+    PasswordError,
+    #[error("Repository error")]
+    RepositoryError,
+}
+
+/// Operator tasks that bypass the login flow, used by the CLI.
+pub struct UserAdminService {
+    user_repository: Box<dyn UserRepository>,
+}
+
+impl UserAdminService {
+    pub fn new(user_repository: Box<dyn UserRepository>) -> Self {
+        Self { user_repository }
+    }
+
+    pub async fn list_users(&self) -> Result<Box<[UserSummary]>, Report<UserAdminError>> {
+        self.user_repository
+            .list_users()
+            .await
+            .change_context(UserAdminError::RepositoryError)
+    }
+
+    /// Also logs the user out everywhere.
+    pub async fn reset_password(
+        &self,
+        username: String,
+        password: String,
+    ) -> Result<(), Report<UserAdminError>> {
+        let password = Password::hash_password(password)
+            .change_context(UserAdminError::PasswordError)?
+            .encode_to_msg_pack()
+            .change_context(UserAdminError::PasswordError)?;
+
+        let user_id = self
+            .user_repository
+            .update_user_password(username, password)
+            .await
+            .map_err(|err| match err.current_context() {
+                UserRepositoryError::NotFoundError => err.change_context(UserAdminError::NotFound),
+                _ => err.change_context(UserAdminError::RepositoryError),
+            })?;
+        self.user_repository
+            .delete_user_tokens(user_id)
+            .await
+            .change_context(UserAdminError::RepositoryError)
+    }
+
+    pub async fn purge_expired_tokens(&self) -> Result<usize, Report<UserAdminError>> {
+        self.user_repository
+            .purge_expired_tokens()
+            .await
+            .change_context(UserAdminError::RepositoryError)
+    }
+}
+
 impl FromContext for UserCheckService {
     async fn from_context(ctx: &'_ Context<'_>) -> Result<Self, Report<ContextError>> {
         let cookie = ctx.req.cookie();
@@ -146,6 +224,36 @@ impl FromUserContext for UserLoginService {
     }
 }
 
+pub struct UserLocaleService {
+    user_repository: Box<dyn UserRepository>,
+}
+
+impl UserLocaleService {
+    pub fn new(user_repository: Box<dyn UserRepository>) -> Self {
+        Self { user_repository }
+    }
+
+    pub async fn save_locale(&self, user_id: i64, locale: Option<String>) -> bool {
+        self.user_repository
+            .update_user_locale(user_id, locale)
+            .await
+            .is_ok()
+    }
+
+    pub async fn save_time_zone(&self, user_id: i64, time_zone: Option<String>) -> bool {
+        self.user_repository
+            .update_user_time_zone(user_id, time_zone)
+            .await
+            .is_ok()
+    }
+}
+
+impl FromUserContext for UserLocaleService {
+    async fn from_user_context(ctx: &'_ UserContext<'_>) -> Result<Self, Report<ContextError>> {
+        Ok(Self::new(ctx.inject_global().await?))
+    }
+}
+
 impl FromUserContext for UserRegisterService {
     async fn from_user_context(ctx: &'_ UserContext<'_>) -> Result<Self, Report<ContextError>> {
         Ok(Self::new(ctx.inject_global().await?))